@@ -0,0 +1,66 @@
+use std::f64::consts::TAU;
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use icos::{icosahedral, Mat3, Norm, Points, Vec3};
+
+/// Points spread evenly over the sphere (Fibonacci lattice).
+fn points(n: usize) -> Vec<Norm<f64>> {
+    let golden = (1.0 + 5.0_f64.sqrt()) / 2.0;
+    (0..n)
+        .map(|i| {
+            let z = 1.0 - (2 * i + 1) as f64 / n as f64;
+            let phi = TAU * i as f64 / golden;
+            Norm::new(z.acos(), phi.rem_euclid(TAU))
+        })
+        .collect()
+}
+
+fn expand(c: &mut Criterion) {
+    let norms = points(5000);
+    let quats = icosahedral();
+    let rotations: Vec<Mat3> = quats.iter().map(Mat3::from_quat).collect();
+
+    let mut group = c.benchmark_group("expand 5000 points x 60 rotations");
+    group.bench_function("norm", |b| {
+        b.iter(|| {
+            quats
+                .iter()
+                .flat_map(|q| norms.iter().map(move |n| q.apply(n)))
+                .collect::<Vec<_>>()
+        })
+    });
+    let p = Points::from(norms.as_slice());
+    group.bench_function("batch", |b| b.iter(|| black_box(&p).expand(&rotations)));
+    group.bench_function("batch + spherical", |b| {
+        b.iter(|| black_box(&p).expand(&rotations).to_spherical())
+    });
+    group.finish();
+}
+
+fn project(c: &mut Criterion) {
+    // Points on a face plane, as produced by subdividing a flat triangle.
+    let flat: Vec<Vec3<f64>> = points(100_000)
+        .iter()
+        .map(|n| {
+            let v = Vec3::from(n);
+            v.scale(&(1.0 / v.z.abs().max(0.5)))
+        })
+        .collect();
+
+    let mut group = c.benchmark_group("project 100000 points");
+    group.bench_function("norm", |b| {
+        b.iter(|| flat.iter().map(|v| v.to_norm()).collect::<Vec<_>>())
+    });
+    let p: Points = flat.iter().cloned().collect();
+    group.bench_function("batch", |b| {
+        b.iter(|| {
+            let mut q = black_box(&p).clone();
+            q.normalise();
+            q.to_spherical()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, expand, project);
+criterion_main!(benches);
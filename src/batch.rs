@@ -0,0 +1,293 @@
+use std::f64::consts::TAU;
+
+use crate::beta;
+use crate::quat::Quat;
+use crate::scalar::ScalarAngle;
+use crate::spherical::Norm;
+use crate::val::Angle;
+use crate::vec3::Vec3;
+
+/// Rotation (or any linear map) as a row-major f64 matrix, for transforming many points.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Mat3(pub [[f64; 3]; 3]);
+
+impl Mat3 {
+    pub fn identity() -> Self {
+        Self([[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]])
+    }
+
+    /// Matrix of a unit quaternion.
+    pub fn from_quat(q: &Quat<f64>) -> Self {
+        Self(q.to_matrix())
+    }
+
+    /// Matrix product `self * a`, i.e. applying `a` first.
+    pub fn mul(&self, a: &Self) -> Self {
+        let (m, n) = (&self.0, &a.0);
+        Self(
+            [0, 1, 2].map(|i| {
+                [0, 1, 2].map(|j| m[i][0] * n[0][j] + m[i][1] * n[1][j] + m[i][2] * n[2][j])
+            }),
+        )
+    }
+
+    /// The inverse, for rotations.
+    pub fn transpose(&self) -> Self {
+        let m = &self.0;
+        Self([0, 1, 2].map(|i| [0, 1, 2].map(|j| m[j][i])))
+    }
+
+    pub fn apply(&self, v: &Vec3<f64>) -> Vec3<f64> {
+        let m = &self.0;
+        Vec3::new(
+            m[0][0] * v.x + m[0][1] * v.y + m[0][2] * v.z,
+            m[1][0] * v.x + m[1][1] * v.y + m[1][2] * v.z,
+            m[2][0] * v.x + m[2][1] * v.y + m[2][2] * v.z,
+        )
+    }
+}
+
+/// The 60 rotations of the icosahedron, with a vertex on the z axis and another on the xz plane.
+/// The identity comes first.
+pub fn icosahedral() -> Vec<Quat<f64>> {
+    let top = Norm::<f64>::zero();
+    // Centre of the face next to the initial meridian.
+    let face = top
+        .south(&f64::from_angle(&beta()))
+        .east(&f64::from_angle(&Angle::part(10)));
+    let gens = [
+        Quat::from_axis_angle(&top, &(TAU / 5.0)),
+        Quat::from_axis_angle(&face, &(TAU / 3.0)),
+    ];
+    // Closure of the generators, breadth first; q and -q are the same rotation.
+    let same = |a: &Quat<f64>, b: &Quat<f64>| {
+        (a.w * b.w + a.x * b.x + a.y * b.y + a.z * b.z).abs() > 1.0 - 1e-9
+    };
+    let mut group = vec![Quat::one()];
+    let mut i = 0;
+    while i < group.len() {
+        for g in gens.iter() {
+            let q = group[i].compose(g);
+            if !group.iter().any(|a| same(a, &q)) {
+                group.push(q);
+            }
+        }
+        i += 1;
+    }
+    group
+}
+
+/// Points in structure-of-arrays layout, so that loops over them can be vectorised.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Points {
+    pub x: Vec<f64>,
+    pub y: Vec<f64>,
+    pub z: Vec<f64>,
+}
+
+impl Points {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_capacity(n: usize) -> Self {
+        Self {
+            x: Vec::with_capacity(n),
+            y: Vec::with_capacity(n),
+            z: Vec::with_capacity(n),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.x.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.x.is_empty()
+    }
+
+    pub fn push(&mut self, v: &Vec3<f64>) {
+        self.x.push(v.x);
+        self.y.push(v.y);
+        self.z.push(v.z);
+    }
+
+    pub fn get(&self, i: usize) -> Vec3<f64> {
+        Vec3::new(self.x[i], self.y[i], self.z[i])
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Vec3<f64>> + '_ {
+        (0..self.len()).map(|i| self.get(i))
+    }
+
+    fn resize(&mut self, n: usize) {
+        self.x.resize(n, 0.0);
+        self.y.resize(n, 0.0);
+        self.z.resize(n, 0.0);
+    }
+
+    pub fn rotate(&self, m: &Mat3) -> Self {
+        let mut out = Self::new();
+        self.rotate_into(m, &mut out);
+        out
+    }
+
+    /// Writes the rotated points into `out`, reusing its buffers.
+    pub fn rotate_into(&self, m: &Mat3, out: &mut Self) {
+        out.resize(self.len());
+        rotate_slices(m, self, &mut out.x, &mut out.y, &mut out.z);
+    }
+
+    /// Images of all points under each of the rotations, grouped by rotation.
+    pub fn expand(&self, rotations: &[Mat3]) -> Self {
+        let n = self.len();
+        let mut out = Self::new();
+        out.resize(n * rotations.len());
+        for (k, m) in rotations.iter().enumerate() {
+            let r = k * n..(k + 1) * n;
+            rotate_slices(
+                m,
+                self,
+                &mut out.x[r.clone()],
+                &mut out.y[r.clone()],
+                &mut out.z[r],
+            );
+        }
+        out
+    }
+
+    /// Central projection onto the unit sphere, e.g. for points on the faces of a polyhedron.
+    pub fn normalise(&mut self) {
+        for ((x, y), z) in self.x.iter_mut().zip(&mut self.y).zip(&mut self.z) {
+            let r = (*x * *x + *y * *y + *z * *z).sqrt().recip();
+            *x *= r;
+            *y *= r;
+            *z *= r;
+        }
+    }
+
+    /// Polar and azimuthal angles of the directions, using the same conventions as `Norm`.
+    pub fn to_spherical(&self) -> (Vec<f64>, Vec<f64>) {
+        let theta = self
+            .x
+            .iter()
+            .zip(&self.y)
+            .zip(&self.z)
+            .map(|((x, y), z)| x.hypot(*y).atan2(*z))
+            .collect();
+        let phi = self
+            .x
+            .iter()
+            .zip(&self.y)
+            .map(|(x, y)| y.atan2(*x).rem_euclid(TAU))
+            .collect();
+        (theta, phi)
+    }
+
+    /// Points on the unit sphere at the given polar and azimuthal angles.
+    pub fn from_spherical(theta: &[f64], phi: &[f64]) -> Self {
+        let mut out = Self::with_capacity(theta.len());
+        for (t, p) in theta.iter().zip(phi) {
+            let (sin, cos) = t.sin_cos();
+            out.x.push(sin * p.cos());
+            out.y.push(sin * p.sin());
+            out.z.push(cos);
+        }
+        out
+    }
+
+    pub fn to_norms(&self) -> Vec<Norm<f64>> {
+        self.iter().map(|v| v.to_norm()).collect()
+    }
+}
+
+impl FromIterator<Vec3<f64>> for Points {
+    fn from_iter<I: IntoIterator<Item = Vec3<f64>>>(iter: I) -> Self {
+        let mut out = Self::new();
+        for v in iter {
+            out.push(&v);
+        }
+        out
+    }
+}
+
+impl From<&[Norm<f64>]> for Points {
+    fn from(n: &[Norm<f64>]) -> Self {
+        n.iter().map(Vec3::from).collect()
+    }
+}
+
+/// The inner loop: straight-line arithmetic over equal-length slices, free of bounds checks.
+fn rotate_slices(m: &Mat3, p: &Points, x: &mut [f64], y: &mut [f64], z: &mut [f64]) {
+    let m = &m.0;
+    let src = p.x.iter().zip(&p.y).zip(&p.z);
+    let dst = x.iter_mut().zip(y.iter_mut()).zip(z.iter_mut());
+    for (((ox, oy), oz), ((x, y), z)) in dst.zip(src) {
+        *ox = m[0][0] * x + m[0][1] * y + m[0][2] * z;
+        *oy = m[1][0] * x + m[1][1] * y + m[1][2] * z;
+        *oz = m[2][0] * x + m[2][1] * y + m[2][2] * z;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::alpha;
+    use approx::assert_relative_eq;
+
+    fn assert_vec_eq(a: &Vec3<f64>, b: &Vec3<f64>) {
+        for (x, y) in a.clone().to_array().iter().zip(b.clone().to_array().iter()) {
+            assert_relative_eq!(x, y, epsilon = 1e-12);
+        }
+    }
+
+    fn vertices() -> Vec<Norm<f64>> {
+        let top = Norm::<f64>::zero();
+        let mut v = vec![top.clone()];
+        for i in 0..5 {
+            v.push(
+                top.south(&f64::from_angle(&alpha()))
+                    .east(&f64::from_angle(&Angle::part(5).imul(i))),
+            );
+        }
+        v
+    }
+
+    #[test]
+    fn test_rotate() {
+        let q = Quat::<f64>::from_axis_angle(&vertices()[2], &1.0);
+        let m = Mat3::from_quat(&q);
+        let p = Points::from(vertices().as_slice());
+        let r = p.rotate(&m);
+        for (i, n) in vertices().iter().enumerate() {
+            assert_vec_eq(&r.get(i), &Vec3::from(q.apply(n)));
+        }
+        assert_vec_eq(&r.rotate(&m.transpose()).get(3), &p.get(3));
+
+        let (theta, phi) = r.to_spherical();
+        let s = Points::from_spherical(&theta, &phi);
+        for (a, b) in s.iter().zip(r.to_norms()) {
+            assert_vec_eq(&a, &Vec3::from(b));
+        }
+    }
+
+    #[test]
+    fn test_icosahedral() {
+        let group: Vec<Mat3> = icosahedral().iter().map(Mat3::from_quat).collect();
+        assert_eq!(group.len(), 60);
+        assert_eq!(group[0], Mat3::identity());
+
+        // The vertices are mapped onto each other.
+        let v = Points::from(vertices().as_slice());
+        let all = v.expand(&group);
+        assert_eq!(all.len(), 360);
+        for p in all.iter() {
+            let d = v
+                .iter()
+                .chain(v.iter().map(|v| v.neg()))
+                .map(|v| v.sub(&p).norm())
+                .fold(f64::INFINITY, f64::min);
+            assert_relative_eq!(d, 0.0, epsilon = 1e-12);
+        }
+    }
+}
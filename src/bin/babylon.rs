@@ -1,6 +1,10 @@
+use std::str::FromStr;
+
 use icos::web::{Consts, Geometry};
+use icos::{Notation, Stellation, Wythoff};
 use rocket::{
     fs::{relative, FileServer},
+    request::FromParam,
     serde::json::Json,
 };
 
@@ -27,6 +31,57 @@ fn goldberg_2_0_json() -> Json<Geometry> {
     Json(Geometry::goldberg_2_0())
 }
 
+/// Largest m and n served, to keep the meshes small enough for the browser.
+const MAX_GOLDBERG: usize = 16;
+
+/// Most faces served for Conway notation.
+const MAX_CONWAY_FACES: usize = 20_000;
+
+/// A path segment like `2.json`, parsed without the extension.
+struct JsonParam<T>(T);
+
+impl<'a, T: FromStr> FromParam<'a> for JsonParam<T> {
+    type Error = &'a str;
+
+    fn from_param(param: &'a str) -> Result<Self, Self::Error> {
+        param
+            .strip_suffix(".json")
+            .and_then(|p| p.parse().ok())
+            .map(Self)
+            .ok_or(param)
+    }
+}
+
+#[get("/goldberg/<m>/<n>")]
+fn goldberg_json(m: usize, n: JsonParam<usize>) -> Option<Json<Geometry>> {
+    let n = n.0;
+    if m > MAX_GOLDBERG || n > MAX_GOLDBERG {
+        return None;
+    }
+    Geometry::goldberg(m, n).map(Json)
+}
+
+#[get("/conway/<notation>")]
+fn conway_json(notation: JsonParam<String>) -> Option<Json<Geometry>> {
+    let notation = Notation::parse(&notation.0)?;
+    if notation.max_faces().is_none_or(|f| f > MAX_CONWAY_FACES) {
+        return None;
+    }
+    Some(Json(Geometry::conway(&notation)))
+}
+
+/// Symbols like `25|3.json`, without the spaces.
+#[get("/wythoff/<symbol>")]
+fn wythoff_json(symbol: JsonParam<String>) -> Option<Json<Geometry>> {
+    Wythoff::parse(&symbol.0).map(|w| Json(Geometry::wythoff(&w)))
+}
+
+/// Notation like `De1f1.json`, or `Ef1'.json` for the chiral half.
+#[get("/stellation/<notation>")]
+fn stellation_json(notation: JsonParam<String>) -> Option<Json<Geometry>> {
+    Stellation::parse(&notation.0).map(|s| Json(Geometry::stellation(&s)))
+}
+
 #[launch]
 fn rocket() -> _ {
     rocket::build()
@@ -38,6 +93,10 @@ fn rocket() -> _ {
                 goldberg_1_0_json,
                 goldberg_1_1_json,
                 goldberg_2_0_json,
+                goldberg_json,
+                conway_json,
+                wythoff_json,
+                stellation_json,
             ],
         )
 }
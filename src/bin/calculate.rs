@@ -12,7 +12,7 @@ fn calculate() {
     let fifth = Angle::part(5);
     let tenth = Angle::part(10);
 
-    let o = Norm::zero().south(&beta()).east(&tenth);
+    let o: Norm = Norm::zero().south(&beta()).east(&tenth);
 
     let mut t: Val = 1.into();
     let mut adjust = t.clone();
@@ -55,7 +55,7 @@ fn _print_formula() {
     let t = Val::param(1);
     let by = alpha().mul(&t).div(&2.into());
 
-    let a = Norm::zero().south(&by);
+    let a: Norm = Norm::zero().south(&by);
     let b = Norm::zero().south(&alpha()).north(&by);
     let c = a.clone().east(&fifth_turn);
 
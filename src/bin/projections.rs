@@ -0,0 +1,26 @@
+use icos::{Geodesic, Projection};
+
+/// Compares the ways of placing subdivided points on the sphere, for a few subdivisions of each class.
+fn main() {
+    println!(
+        "{:<10} {:<12} {:>10} {:>10} {:>8} {:>10} {:>10} {:>8}",
+        "(m, n)", "projection", "edge min", "edge max", "ratio", "area min", "area max", "ratio"
+    );
+    for (m, n) in [(2, 0), (4, 0), (8, 0), (2, 2), (4, 4), (2, 1), (5, 3)] {
+        for p in Projection::all() {
+            let g = Geodesic::<f64>::with_projection(m, n, p).expect("not empty");
+            let (e, a) = (g.edge_spread(), g.area_spread());
+            println!(
+                "{:<10} {:<12} {:>10.6} {:>10.6} {:>8.4} {:>10.6} {:>10.6} {:>8.4}",
+                format!("({m}, {n})"),
+                format!("{p:?}"),
+                e.min,
+                e.max,
+                e.ratio(),
+                a.min,
+                a.max,
+                a.ratio(),
+            );
+        }
+    }
+}
@@ -0,0 +1,359 @@
+use num_traits::Zero;
+
+use crate::spherical::Norm;
+use crate::val::{Angle, Cond, Val};
+use crate::vec3::Vec3;
+
+/// Great circle on the unit sphere, represented by its pole.
+/// The circle is oriented counterclockwise when looking down at the pole.
+#[derive(Clone)]
+pub struct GreatCircle {
+    pole: Norm,
+}
+
+impl GreatCircle {
+    /// The great circle having the given pole.
+    pub fn new(pole: Norm) -> Self {
+        Self { pole }
+    }
+
+    /// The great circle through `a` then `b`; undefined for equal or antipodal points.
+    pub fn through(a: &Norm, b: &Norm) -> Self {
+        Self::new(Vec3::from(a).cross(&b.into()).simplify_trig().to_norm())
+    }
+
+    /// The equator, oriented eastwards.
+    pub fn equator() -> Self {
+        Self::new(Norm::zero())
+    }
+
+    pub fn pole(&self) -> &Norm {
+        &self.pole
+    }
+
+    /// Signed angular distance of a point from the circle, positive on the side of the pole.
+    pub fn distance_to(&self, p: &Norm) -> Angle {
+        Vec3::from(&self.pole).dot(&p.into()).simplify_trig().asin()
+    }
+
+    /// The two points where the circles meet, or `None` if they coincide
+    /// or can't be told apart numerically.
+    /// The first point is where `other` crosses this circle from right to left.
+    pub fn intersect(&self, other: &Self) -> Option<[Norm; 2]> {
+        let (n1, n2) = (Vec3::from(&self.pole), Vec3::from(&other.pole));
+        let w = n1.cross(&n2).simplify_trig();
+        if parallel(&n1, &n2, &w.norm_sq().simplify_trig()).unwrap_or(false) {
+            return None;
+        }
+        let p = w.to_norm();
+        let q = p.antipode();
+        Some([p, q])
+    }
+
+    /// Point at `by` along the circle, starting from where it crosses `from`.
+    pub fn point_at(&self, from: &Norm, by: &Angle) -> Norm {
+        let n = Vec3::from(&self.pole);
+        let a = Vec3::from(from);
+        // Project the starting point onto the plane of the circle.
+        let a = a.sub(&n.scale(&n.dot(&a))).simplify_trig().normalise();
+        let b = n.cross(&a);
+        a.scale(&by.cos())
+            .add(&b.scale(&by.sin()))
+            .simplify_trig()
+            .to_norm()
+    }
+
+    /// Checks exactly whether a point lies on the circle, if it can be decided.
+    pub fn contains(&self, p: &Norm) -> Option<bool> {
+        let d = Vec3::from(&self.pole).dot(&p.into()).simplify_trig();
+        match d.to_rational() {
+            Some(x) => Some(x.is_zero()),
+            None => d.to_interval().filter(|i| !i.contains(0.0)).map(|_| false),
+        }
+    }
+}
+
+/// Small circle on the unit sphere: the points at angular distance `radius` from `centre`.
+/// A radius of a quarter turn gives a great circle.
+#[derive(Clone)]
+pub struct SmallCircle {
+    centre: Norm,
+    radius: Angle,
+}
+
+impl SmallCircle {
+    pub fn new(centre: Norm, radius: Angle) -> Self {
+        Self { centre, radius }
+    }
+
+    pub fn centre(&self) -> &Norm {
+        &self.centre
+    }
+
+    pub fn radius(&self) -> &Angle {
+        &self.radius
+    }
+
+    /// Area of the cap inside the circle, in steradians: 2pi(1 - cos r).
+    pub fn cap_area(&self) -> Angle {
+        Angle::turn().mul(&Val::from(1).sub(&self.radius.cos()).simplify_trig())
+    }
+
+    /// Length of the circle: 2pi sin r.
+    pub fn circumference(&self) -> Angle {
+        Angle::turn().mul(&self.radius.sin().simplify_trig())
+    }
+
+    /// Checks whether a point is within the cap, if it can be certified.
+    pub fn contains(&self, p: &Norm) -> Option<bool> {
+        let d = Vec3::from(&self.centre).dot(&p.into()).simplify_trig();
+        Cond::le(&self.radius.cos().simplify_trig(), &d).to_certified()
+    }
+
+    /// The points where the circles meet; both are the same point where they touch.
+    /// Returns `None` if the circles certainly don't meet, or are concentric or too close to it to tell.
+    /// Where that can't be certified, e.g. with parameters, both points are the same where the circles don't meet:
+    /// the direction of the line where their planes meet, closest to the centre.
+    /// Going around this circle counterclockwise, the first point is where it enters the other one.
+    pub fn intersect(&self, other: &Self) -> Option<[Norm; 2]> {
+        let (n1, n2) = (Vec3::from(&self.centre), Vec3::from(&other.centre));
+        let (c1, c2) = (self.radius.cos(), other.radius.cos());
+        let w = n2.cross(&n1).simplify_trig();
+        let w2 = w.norm_sq().simplify_trig();
+        if parallel(&n1, &n2, &w2).unwrap_or(false) {
+            return None;
+        }
+
+        // The line where the planes of the circles meet: x0 + t w.
+        let d = n1.dot(&n2).simplify_trig();
+        let a = c1.sub(&c2.mul(&d)).div(&w2);
+        let b = c2.sub(&c1.mul(&d)).div(&w2);
+        let x0 = n1.scale(&a).add(&n2.scale(&b));
+        let t2 = Val::from(1).sub(&x0.norm_sq()).div(&w2).simplify_trig();
+        let apart = Cond::lt(&t2, &0.into());
+        let t2 = match apart.to_certified() {
+            Some(true) => return None,
+            Some(false) => t2,
+            None => Val::piecewise(&apart, &0.into(), &t2),
+        };
+        let t = w.scale(&t2.sqrt());
+        Some([x0.add(&t).to_norm(), x0.sub(&t).to_norm()])
+    }
+
+    /// The points where this circle meets a great circle.
+    pub fn intersect_great(&self, other: &GreatCircle) -> Option<[Norm; 2]> {
+        self.intersect(&other.clone().into())
+    }
+}
+
+impl From<GreatCircle> for SmallCircle {
+    fn from(c: GreatCircle) -> Self {
+        Self::new(c.pole, Angle::part(4))
+    }
+}
+
+/// Whether two axes are parallel, given the squared length `w2` of their cross product.
+/// Certified using intervals when not exact; axes too close to tell apart count as parallel.
+fn parallel(n1: &Vec3<Val>, n2: &Vec3<Val>, w2: &Val) -> Option<bool> {
+    if *n1 == *n2 || *n1 == n2.neg() {
+        return Some(true);
+    }
+    match w2.to_rational() {
+        Some(x) => Some(x.is_zero()),
+        None => w2.to_interval().map(|i| i.contains(0.0)),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::testing::{assert_close, to_f64};
+    use crate::{alpha, beta};
+    use approx::assert_relative_eq;
+    use num_rational::Ratio;
+    use num_traits::ToPrimitive;
+
+    #[test]
+    fn test_through() {
+        let a = Norm::zero().south(&Angle::part(4));
+        let b = a.east(&Angle::part(4));
+        let c = GreatCircle::through(&a, &b);
+        assert_eq!(c.pole().theta().to_rational(), Some(Zero::zero()));
+        assert_eq!(c.contains(&a.east(&Angle::part(3))), Some(true));
+        assert_eq!(c.contains(&Norm::zero().south(&alpha())), Some(false));
+        assert_eq!(
+            c.distance_to(&Norm::zero()).to_rational(),
+            Angle::part(4).to_rational()
+        );
+
+        let p = c.point_at(&a, &Angle::part(8));
+        assert_eq!(p.phi().to_rational(), Angle::part(8).to_rational());
+    }
+
+    #[test]
+    fn test_intersect() {
+        let meridian = GreatCircle::through(&Norm::zero(), &Norm::zero().south(&Angle::part(4)));
+        let [p, q] = GreatCircle::equator().intersect(&meridian).unwrap();
+        assert_eq!(p.theta().to_rational(), Angle::part(4).to_rational());
+        assert_eq!(p.phi().to_rational(), Angle::part(2).to_rational());
+        assert_eq!(q.phi().to_rational(), Angle::zero().to_rational());
+        assert!(meridian.intersect(&meridian).is_none());
+
+        // An irrational pole, with itself and its antipode.
+        let c = GreatCircle::new(Norm::zero().south(&alpha()).east(&Angle::part(5)));
+        assert!(c.intersect(&c).is_none());
+        assert!(c
+            .intersect(&GreatCircle::new(c.pole().antipode()))
+            .is_none());
+
+        // Two icosahedron edges meeting at a vertex.
+        let top = Norm::zero();
+        let v = |i: i64| top.south(&alpha()).east(&Angle::part(5).imul(i));
+        let [p, q] = GreatCircle::through(&top, &v(0))
+            .intersect(&GreatCircle::through(&v(0), &v(1)))
+            .unwrap();
+        let d = p
+            .angle_to(&v(0))
+            .to_f64()
+            .unwrap()
+            .min(q.angle_to(&v(0)).to_f64().unwrap());
+        assert_relative_eq!(d, 0.0, epsilon = 1e-7);
+    }
+
+    #[test]
+    fn test_cap() {
+        let c = SmallCircle::new(Norm::zero(), Angle::part(6));
+        assert_eq!(
+            c.cap_area().to_rational(),
+            Some(Ratio::new(1.into(), 1.into()))
+        );
+        assert_relative_eq!(
+            c.circumference().to_f64().unwrap(),
+            std::f64::consts::PI * 3.0_f64.sqrt()
+        );
+        assert_eq!(c.contains(&Norm::zero().south(&beta())), Some(true));
+        assert_eq!(c.contains(&Norm::zero().south(&alpha())), Some(false));
+        assert_eq!(
+            c.contains(&Norm::zero().south(&Angle::part(4))),
+            Some(false)
+        );
+        assert_eq!(c.contains(&Norm::zero().south(&Angle::part(6))), Some(true));
+
+        // The northern hemisphere, as a small circle.
+        let h: SmallCircle = GreatCircle::equator().into();
+        assert_eq!(h.cap_area().to_rational(), Angle::turn().to_rational());
+    }
+
+    #[test]
+    fn test_intersect_small() {
+        // Circles of radius pi/3 around two points a quarter turn apart on the equator.
+        let a = Norm::zero().south(&Angle::part(4));
+        let b = a.east(&Angle::part(4));
+        let r = Angle::part(6);
+        let [p, q] = SmallCircle::new(a.clone(), r.clone())
+            .intersect(&SmallCircle::new(b.clone(), r.clone()))
+            .unwrap();
+        for x in [&p, &q] {
+            assert_relative_eq!(
+                x.angle_to(&a).to_f64().unwrap(),
+                r.to_f64().unwrap(),
+                epsilon = 1e-12
+            );
+            assert_relative_eq!(
+                x.angle_to(&b).to_f64().unwrap(),
+                r.to_f64().unwrap(),
+                epsilon = 1e-12
+            );
+            assert_relative_eq!(
+                x.phi().to_f64().unwrap(),
+                Angle::part(8).to_f64().unwrap(),
+                epsilon = 1e-12
+            );
+        }
+        assert!(p.z().to_f64().unwrap() < 0.0 && q.z().to_f64().unwrap() > 0.0);
+
+        // Too far apart.
+        let small = Angle::part(12);
+        assert!(SmallCircle::new(a.clone(), small.clone())
+            .intersect(&SmallCircle::new(b.clone(), small))
+            .is_none());
+        assert!(SmallCircle::new(a.clone(), r.clone())
+            .intersect(&SmallCircle::new(a.clone(), r.clone()))
+            .is_none());
+
+        // Concentric around an irrational centre.
+        let c = Norm::zero().south(&alpha()).east(&Angle::part(5));
+        assert!(SmallCircle::new(c.clone(), Angle::part(16))
+            .intersect(&SmallCircle::new(c.clone(), Angle::part(24)))
+            .is_none());
+        assert!(SmallCircle::new(c.clone(), Angle::part(16))
+            .intersect(&SmallCircle::new(c.antipode(), Angle::part(24)))
+            .is_none());
+
+        // With a parameter, the points are only apart where the circles meet, and never NaN.
+        let [p, q] = SmallCircle::new(a.clone(), r.mul(&Val::param(1)))
+            .intersect(&SmallCircle::new(b.clone(), r.mul(&Val::param(1))))
+            .unwrap();
+        let angle = |x: &Norm, t: &Val, c: &Norm| {
+            let x = Vec3::from(x)
+                .to_array()
+                .map(|v| v.subs(1, t).to_f64().unwrap());
+            let c = Vec3::from(c).to_array().map(|v| v.to_f64().unwrap());
+            (x[0] * c[0] + x[1] * c[1] + x[2] * c[2]).acos()
+        };
+        assert_relative_eq!(
+            angle(&p, &1.into(), &b),
+            r.to_f64().unwrap(),
+            epsilon = 1e-12
+        );
+        assert_relative_eq!(
+            angle(&q, &1.into(), &a),
+            r.to_f64().unwrap(),
+            epsilon = 1e-12
+        );
+        let half = Val::from(1).idiv(2);
+        let (x, y) = (angle(&p, &half, &a), angle(&q, &half, &a));
+        assert!(x.is_finite());
+        assert_relative_eq!(x, y, epsilon = 1e-12);
+        assert_relative_eq!(angle(&p, &half, &b), x, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_goldberg_corner() {
+        // In GP(2, 0), the hexagon vertex in the corner of a face at v_1 is on the median through v_1,
+        // as far from the face centre as r_0 on the median through the top; i.e. r_0 turned a third about the centre.
+        let by = beta().mul(&Val::from(0.42));
+        let fifth = Angle::part(5);
+        let o = Norm::zero().south(&beta()).east(&Angle::part(10));
+        let r_0 = Norm::zero().south(&by).east(&Angle::part(10));
+        let v_1 = Norm::zero().south(&alpha()).east(&fifth);
+        let [_, corner] = SmallCircle::new(o.approximate(0), beta().sub(&by).approximate(0))
+            .intersect_great(&GreatCircle::through(
+                &v_1.approximate(0),
+                &o.approximate(0),
+            ))
+            .unwrap();
+        let turned = r_0.rot(&o, &Angle::part(3).neg());
+        assert_close(&to_f64(&corner.into()), &to_f64(&turned.into()));
+    }
+
+    #[test]
+    fn test_intersect_great() {
+        // Points at alpha from the pole on the meridian plane.
+        let c = SmallCircle::new(Norm::zero(), alpha());
+        let meridian = GreatCircle::through(&Norm::zero(), &Norm::zero().south(&Angle::part(4)));
+        let [p, q] = c.intersect_great(&meridian).unwrap();
+        assert_relative_eq!(p.y().to_f64().unwrap(), 0.0, epsilon = 1e-12);
+        assert_relative_eq!(q.y().to_f64().unwrap(), 0.0, epsilon = 1e-12);
+        assert_relative_eq!(
+            (p.x().to_f64().unwrap() * q.x().to_f64().unwrap()),
+            -alpha().sin().to_f64().unwrap().powi(2),
+            epsilon = 1e-12
+        );
+        assert_relative_eq!(
+            p.z().to_f64().unwrap(),
+            alpha().cos().to_f64().unwrap(),
+            epsilon = 1e-12
+        );
+    }
+}
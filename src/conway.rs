@@ -0,0 +1,547 @@
+use std::collections::HashMap;
+
+use crate::mesh::Mesh;
+use crate::platonic::Platonic;
+use crate::scalar::Scalar;
+use crate::vec3::Vec3;
+
+/// Conway polyhedron operators.
+/// Positions of new points are simple averages, so the results are not canonical, but keep their symmetry.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Op {
+    /// `d`: faces become vertices, at the face centres.
+    Dual,
+    /// `a`: vertices at the edge midpoints.
+    Ambo,
+    /// `k`: a pyramid on each face, or only on those with the given number of sides.
+    Kis(Option<usize>),
+    /// `t`: cut off each vertex, or only those of the given degree, at a third of the edges.
+    Truncate(Option<usize>),
+    /// `j`: a rhombus for each edge, the dual of ambo.
+    Join,
+    /// `c`: faces shrink, and edges become hexagons.
+    Chamfer,
+    /// `e`: faces and vertices pull apart, leaving a square for each edge; ambo applied twice.
+    Expand,
+    /// `s`: like expand, with the squares cut into triangles and twisted.
+    Snub,
+    /// `g`: each n-gon becomes n pentagons, the dual of snub.
+    Gyro,
+    /// `w`: each n-gon becomes a smaller twisted copy, surrounded by n hexagons.
+    Whirl,
+}
+
+impl Op {
+    pub fn symbol(&self) -> char {
+        match self {
+            Self::Dual => 'd',
+            Self::Ambo => 'a',
+            Self::Kis(_) => 'k',
+            Self::Truncate(_) => 't',
+            Self::Join => 'j',
+            Self::Chamfer => 'c',
+            Self::Expand => 'e',
+            Self::Snub => 's',
+            Self::Gyro => 'g',
+            Self::Whirl => 'w',
+        }
+    }
+
+    fn from_symbol(c: char, n: Option<usize>) -> Option<Self> {
+        let op = match c {
+            // Faces and vertices have at least three sides.
+            'k' | 't' if n.is_some_and(|n| n < 3) => return None,
+            'k' => return Some(Self::Kis(n)),
+            't' => return Some(Self::Truncate(n)),
+            'd' => Self::Dual,
+            'a' => Self::Ambo,
+            'j' => Self::Join,
+            'c' => Self::Chamfer,
+            'e' => Self::Expand,
+            's' => Self::Snub,
+            'g' => Self::Gyro,
+            'w' => Self::Whirl,
+            _ => return None,
+        };
+        // Only kis and truncate take a number.
+        n.is_none().then_some(op)
+    }
+
+    /// Applies the operator to a closed mesh.
+    pub fn apply<S: Scalar>(&self, m: &Mesh<Vec3<S>>) -> Mesh<Vec3<S>> {
+        match self {
+            Self::Dual => dual(m),
+            Self::Ambo => ambo(m),
+            Self::Kis(n) => kis(m, *n),
+            Self::Truncate(n) => truncate(m, *n),
+            Self::Join => join(m),
+            Self::Chamfer => chamfer(m),
+            Self::Expand => ambo(&ambo(m)),
+            Self::Snub => snub(m),
+            Self::Gyro => gyro(m),
+            Self::Whirl => whirl(m),
+        }
+    }
+}
+
+/// Conway notation: operators applied right to left to a Platonic seed, e.g. `dk5aI`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Notation {
+    /// In the order they are written, i.e. the last one is applied first.
+    pub ops: Vec<Op>,
+    pub seed: Platonic,
+}
+
+impl Notation {
+    /// Parses operators, each optionally followed by a number, then one of the seeds `T`, `C`, `O`, `D` or `I`.
+    pub fn parse(s: &str) -> Option<Self> {
+        let seed = match s.chars().last()? {
+            'T' => Platonic::Tetrahedron,
+            'C' => Platonic::Cube,
+            'O' => Platonic::Octahedron,
+            'D' => Platonic::Dodecahedron,
+            'I' => Platonic::Icosahedron,
+            _ => return None,
+        };
+        let mut ops = vec![];
+        let mut chars = s[..s.len() - 1].chars().peekable();
+        while let Some(c) = chars.next() {
+            let mut digits = String::new();
+            while let Some(d) = chars.next_if(char::is_ascii_digit) {
+                digits.push(d);
+            }
+            let n = match digits.as_str() {
+                "" => None,
+                d => Some(d.parse().ok()?),
+            };
+            ops.push(Op::from_symbol(c, n)?);
+        }
+        Some(Self { ops, seed })
+    }
+
+    /// Number of faces the result will have, without building it;
+    /// an upper bound if kis or truncate are restricted to some faces or vertices.
+    /// Returns `None` if the count doesn't fit in a `usize`.
+    pub fn max_faces(&self) -> Option<usize> {
+        let (v, e, f) = self.seed.counts();
+        let (mut v, mut e, mut f) = (v as usize, e as usize, f as usize);
+        for op in self.ops.iter().rev() {
+            (v, e, f) = match op {
+                Op::Dual => (f, e, v),
+                Op::Ambo => (e, lin(&[(2, e)])?, lin(&[(1, f), (1, v)])?),
+                Op::Kis(_) => (lin(&[(1, v), (1, f)])?, lin(&[(3, e)])?, lin(&[(2, e)])?),
+                Op::Truncate(_) => (lin(&[(2, e)])?, lin(&[(3, e)])?, lin(&[(1, f), (1, v)])?),
+                Op::Join => (lin(&[(1, v), (1, f)])?, lin(&[(2, e)])?, e),
+                Op::Chamfer => (
+                    lin(&[(1, v), (2, e)])?,
+                    lin(&[(4, e)])?,
+                    lin(&[(1, f), (1, e)])?,
+                ),
+                Op::Expand => (
+                    lin(&[(2, e)])?,
+                    lin(&[(4, e)])?,
+                    lin(&[(1, v), (1, e), (1, f)])?,
+                ),
+                Op::Snub => (
+                    lin(&[(2, e)])?,
+                    lin(&[(5, e)])?,
+                    lin(&[(1, v), (2, e), (1, f)])?,
+                ),
+                Op::Gyro => (
+                    lin(&[(1, v), (2, e), (1, f)])?,
+                    lin(&[(5, e)])?,
+                    lin(&[(2, e)])?,
+                ),
+                Op::Whirl => (
+                    lin(&[(1, v), (4, e)])?,
+                    lin(&[(7, e)])?,
+                    lin(&[(1, f), (2, e)])?,
+                ),
+            };
+        }
+        Some(f)
+    }
+
+    pub fn mesh<S: Scalar>(&self) -> Mesh<Vec3<S>> {
+        self.ops
+            .iter()
+            .rev()
+            .fold(self.seed.mesh(), |m, op| op.apply(&m))
+    }
+}
+
+/// Sum of the counts `x` times `k`, or `None` on overflow.
+fn lin(terms: &[(usize, usize)]) -> Option<usize> {
+    terms
+        .iter()
+        .try_fold(0usize, |sum, &(k, x)| sum.checked_add(k.checked_mul(x)?))
+}
+
+/// Edge index of each half-edge, shared with its twin, in the order of `Mesh::edges`.
+fn edge_ids<P>(m: &Mesh<P>) -> Vec<usize> {
+    let mut ids = vec![0; m.half_edges().len()];
+    for (i, h) in m.edges().enumerate() {
+        ids[h] = i;
+        if let Some(t) = m.half_edge(h).twin {
+            ids[t] = i;
+        }
+    }
+    ids
+}
+
+fn twin<P>(m: &Mesh<P>, h: usize) -> usize {
+    m.half_edge(h).twin.expect("closed mesh")
+}
+
+/// The point `p / q` of the way from `a` to `b`.
+fn lerp<S: Scalar>(a: &Vec3<S>, b: &Vec3<S>, p: i64, q: i64) -> Vec3<S> {
+    a.add(&b.sub(a).scale(&S::int(p).div(&S::int(q))))
+}
+
+fn centroid<S: Scalar>(m: &Mesh<Vec3<S>>, f: usize) -> Vec3<S> {
+    let v = m.face_vertices(f);
+    let sum = v
+        .iter()
+        .fold(Vec3::zero(), |sum, &v| sum.add(m.position(v)));
+    sum.scale(&S::int(1).div(&S::int(v.len() as i64)))
+}
+
+/// Above the centroid, as far from the centre as the face's vertices on average.
+fn apex<S: Scalar>(m: &Mesh<Vec3<S>>, f: usize) -> Vec3<S> {
+    let v = m.face_vertices(f);
+    let r = v
+        .iter()
+        .fold(S::int(0), |r, &v| r.add(&m.position(v).norm()))
+        .div(&S::int(v.len() as i64));
+    centroid(m, f).normalise().scale(&r)
+}
+
+/// Drops the points not used by any face, then builds the mesh.
+fn build<S: Scalar>(positions: Vec<Vec3<S>>, faces: Vec<Vec<usize>>) -> Mesh<Vec3<S>> {
+    let mut index = HashMap::new();
+    let mut used = vec![];
+    let faces: Vec<Vec<usize>> = faces
+        .into_iter()
+        .map(|face| {
+            face.into_iter()
+                .map(|v| {
+                    *index.entry(v).or_insert_with(|| {
+                        used.push(positions[v].clone());
+                        used.len() - 1
+                    })
+                })
+                .collect()
+        })
+        .collect();
+    Mesh::from_faces(used, &faces).expect("consistent winding")
+}
+
+fn dual<S: Scalar>(m: &Mesh<Vec3<S>>) -> Mesh<Vec3<S>> {
+    let positions = m.faces().map(|f| centroid(m, f)).collect();
+    let faces = m.vertices().map(|v| m.vertex_faces(v)).collect();
+    build(positions, faces)
+}
+
+fn ambo<S: Scalar>(m: &Mesh<Vec3<S>>) -> Mesh<Vec3<S>> {
+    let ids = edge_ids(m);
+    let positions = m
+        .edges()
+        .map(|h| {
+            lerp(
+                m.position(m.half_edge(h).origin),
+                m.position(m.dest(h)),
+                1,
+                2,
+            )
+        })
+        .collect();
+    let edges = |hs: Vec<usize>| hs.into_iter().map(|h| ids[h]).collect();
+    let faces = m
+        .faces()
+        .map(|f| edges(m.face_half_edges(f)))
+        .chain(m.vertices().map(|v| edges(m.vertex_half_edges(v))))
+        .collect();
+    build(positions, faces)
+}
+
+fn kis<S: Scalar>(m: &Mesh<Vec3<S>>, n: Option<usize>) -> Mesh<Vec3<S>> {
+    let mut positions = m.positions().to_vec();
+    let mut faces = vec![];
+    for f in m.faces() {
+        let v = m.face_vertices(f);
+        if n.is_some_and(|n| n != v.len()) {
+            faces.push(v);
+            continue;
+        }
+        positions.push(apex(m, f));
+        let c = positions.len() - 1;
+        faces.extend((0..v.len()).map(|i| vec![v[i], v[(i + 1) % v.len()], c]));
+    }
+    build(positions, faces)
+}
+
+fn truncate<S: Scalar>(m: &Mesh<Vec3<S>>, n: Option<usize>) -> Mesh<Vec3<S>> {
+    let cut = |v: usize| n.is_none_or(|n| n == m.vertex_half_edges(v).len());
+    // Original vertices first, then a point a third of the way along each half-edge.
+    let mut positions = m.positions().to_vec();
+    positions.extend((0..m.half_edges().len()).map(|h| {
+        lerp(
+            m.position(m.half_edge(h).origin),
+            m.position(m.dest(h)),
+            1,
+            3,
+        )
+    }));
+    let point = |h: usize| {
+        let v = m.half_edge(h).origin;
+        if cut(v) {
+            m.vertex_count() + h
+        } else {
+            v
+        }
+    };
+    let mut faces: Vec<Vec<usize>> = m
+        .faces()
+        .map(|f| {
+            let mut face: Vec<usize> = m
+                .face_half_edges(f)
+                .into_iter()
+                .flat_map(|h| [point(h), point(twin(m, h))])
+                .collect();
+            // Corners that are not cut appear twice in a row.
+            face.dedup();
+            if face.first() == face.last() {
+                face.pop();
+            }
+            face
+        })
+        .collect();
+    faces.extend(
+        m.vertices()
+            .filter(|&v| cut(v))
+            .map(|v| m.vertex_half_edges(v).into_iter().map(point).collect()),
+    );
+    build(positions, faces)
+}
+
+fn join<S: Scalar>(m: &Mesh<Vec3<S>>) -> Mesh<Vec3<S>> {
+    // Original vertices, then the face centres.
+    let mut positions = m.positions().to_vec();
+    positions.extend(m.faces().map(|f| apex(m, f)));
+    let centre = |h: usize| m.vertex_count() + m.half_edge(h).face;
+    let faces = m
+        .edges()
+        .map(|h| {
+            let t = twin(m, h);
+            vec![m.half_edge(h).origin, centre(t), m.dest(h), centre(h)]
+        })
+        .collect();
+    build(positions, faces)
+}
+
+fn chamfer<S: Scalar>(m: &Mesh<Vec3<S>>) -> Mesh<Vec3<S>> {
+    // Original vertices, then for each half-edge a point inside its face, near its origin.
+    let mut positions = m.positions().to_vec();
+    let centres: Vec<Vec3<S>> = m.faces().map(|f| centroid(m, f)).collect();
+    positions.extend((0..m.half_edges().len()).map(|h| {
+        let e = m.half_edge(h);
+        lerp(m.position(e.origin), &centres[e.face], 1, 3)
+    }));
+    let inner = |h: usize| m.vertex_count() + h;
+    let mut faces: Vec<Vec<usize>> = m
+        .faces()
+        .map(|f| m.face_half_edges(f).into_iter().map(inner).collect())
+        .collect();
+    faces.extend(m.edges().map(|h| {
+        let t = twin(m, h);
+        let (next, tnext) = (m.half_edge(h).next, m.half_edge(t).next);
+        vec![
+            m.half_edge(h).origin,
+            inner(tnext),
+            inner(t),
+            m.dest(h),
+            inner(next),
+            inner(h),
+        ]
+    }));
+    build(positions, faces)
+}
+
+fn snub<S: Scalar>(m: &Mesh<Vec3<S>>) -> Mesh<Vec3<S>> {
+    // A point for each corner of each face, i.e. each half-edge; near its origin, turned towards its end.
+    let centres: Vec<Vec3<S>> = m.faces().map(|f| centroid(m, f)).collect();
+    let positions = (0..m.half_edges().len())
+        .map(|h| {
+            let e = m.half_edge(h);
+            let a = m.position(e.origin);
+            let corner = lerp(a, &centres[e.face], 1, 3);
+            lerp(&corner, &lerp(a, m.position(m.dest(h)), 1, 2), 1, 3)
+        })
+        .collect();
+    let mut faces: Vec<Vec<usize>> = m.faces().map(|f| m.face_half_edges(f)).collect();
+    faces.extend(m.vertices().map(|v| m.vertex_half_edges(v)));
+    for h in m.edges() {
+        // The corners around the edge, counterclockwise from the one before its origin on the other side.
+        let t = twin(m, h);
+        let [sw, se, ne, nw] = [m.half_edge(t).next, t, m.half_edge(h).next, h];
+        faces.push(vec![sw, se, nw]);
+        faces.push(vec![se, ne, nw]);
+    }
+    build(positions, faces)
+}
+
+fn gyro<S: Scalar>(m: &Mesh<Vec3<S>>) -> Mesh<Vec3<S>> {
+    // Original vertices, face centres, then a point a third of the way along each half-edge.
+    let (nv, nf) = (m.vertex_count(), m.face_count());
+    let mut positions = m.positions().to_vec();
+    positions.extend(m.faces().map(|f| apex(m, f)));
+    positions.extend((0..m.half_edges().len()).map(|h| {
+        lerp(
+            m.position(m.half_edge(h).origin),
+            m.position(m.dest(h)),
+            1,
+            3,
+        )
+    }));
+    let third = |h: usize| nv + nf + h;
+    // Spokes run from the centre to the point near the end of each edge.
+    let faces = (0..m.half_edges().len())
+        .map(|h| {
+            let e = m.half_edge(h);
+            vec![
+                nv + e.face,
+                third(twin(m, e.prev)),
+                e.origin,
+                third(h),
+                third(twin(m, h)),
+            ]
+        })
+        .collect();
+    build(positions, faces)
+}
+
+fn whirl<S: Scalar>(m: &Mesh<Vec3<S>>) -> Mesh<Vec3<S>> {
+    // Original vertices, a point a third of the way along each half-edge,
+    // then an inner point for each, halfway from the face centre to the point near the end of the edge.
+    let (nv, nh) = (m.vertex_count(), m.half_edges().len());
+    let centres: Vec<Vec3<S>> = m.faces().map(|f| centroid(m, f)).collect();
+    let thirds: Vec<Vec3<S>> = (0..nh)
+        .map(|h| {
+            lerp(
+                m.position(m.half_edge(h).origin),
+                m.position(m.dest(h)),
+                1,
+                3,
+            )
+        })
+        .collect();
+    let mut positions = m.positions().to_vec();
+    positions.extend(thirds.iter().cloned());
+    positions.extend((0..nh).map(|h| {
+        let e = m.half_edge(h);
+        lerp(&centres[e.face], &thirds[twin(m, h)], 1, 2)
+    }));
+    let (third, inner) = (|h: usize| nv + h, |h: usize| nv + nh + h);
+    let mut faces: Vec<Vec<usize>> = m
+        .faces()
+        .map(|f| m.face_half_edges(f).into_iter().map(inner).collect())
+        .collect();
+    faces.extend((0..nh).map(|h| {
+        let e = m.half_edge(h);
+        vec![
+            inner(e.prev),
+            third(twin(m, e.prev)),
+            e.origin,
+            third(h),
+            third(twin(m, h)),
+            inner(h),
+        ]
+    }));
+    build(positions, faces)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::testing::{counts, outward};
+    use crate::val::Val;
+    use approx::assert_relative_eq;
+    use num_traits::ToPrimitive;
+
+    #[test]
+    fn test_operators() {
+        let seed: Mesh<Vec3<f64>> = Platonic::Cube.mesh();
+        let (v, e, f) = counts(&seed);
+        let table = [
+            (Op::Dual, (f, e, v)),
+            (Op::Ambo, (e, 2 * e, f + v)),
+            (Op::Kis(None), (v + f, 3 * e, 2 * e)),
+            (Op::Truncate(None), (2 * e, 3 * e, f + v)),
+            (Op::Join, (v + f, 2 * e, e)),
+            (Op::Chamfer, (v + 2 * e, 4 * e, f + e)),
+            (Op::Expand, (2 * e, 4 * e, v + e + f)),
+            (Op::Snub, (2 * e, 5 * e, v + 2 * e + f)),
+            (Op::Gyro, (v + 2 * e + f, 5 * e, 2 * e)),
+            (Op::Whirl, (v + 4 * e, 7 * e, f + 2 * e)),
+        ];
+        for (op, expected) in table {
+            let m = op.apply(&seed);
+            assert_eq!(counts(&m), expected, "{op:?}");
+            assert!(m.is_closed());
+            assert!(outward(&m), "{op:?}");
+            assert_eq!(
+                Notation {
+                    ops: vec![op],
+                    seed: Platonic::Cube
+                }
+                .max_faces(),
+                Some(expected.2)
+            );
+        }
+
+        // Restricted to the pentagons of the icosidodecahedron, or to the 5-fold vertices of its dual.
+        let ai: Mesh<Vec3<f64>> = Op::Ambo.apply(&Platonic::Icosahedron.mesh());
+        let k = Op::Kis(Some(5)).apply(&ai);
+        assert_eq!(counts(&k), (42, 120, 80));
+        let t = Op::Truncate(Some(5)).apply(&Op::Dual.apply(&ai));
+        assert_eq!(counts(&t), (80, 120, 42));
+        assert!(outward(&k) && outward(&t));
+    }
+
+    #[test]
+    fn test_notation() {
+        let n = Notation::parse("dk5aI").unwrap();
+        assert_eq!(n.ops, vec![Op::Dual, Op::Kis(Some(5)), Op::Ambo]);
+        assert_eq!(n.seed, Platonic::Icosahedron);
+        let m: Mesh<Vec3<f64>> = n.mesh();
+        assert_eq!(counts(&m), (80, 120, 42));
+        assert!(n.max_faces() >= Some(42));
+
+        // Too many faces to count, let alone build.
+        let w = Notation::parse(&format!("{}I", "w".repeat(22))).unwrap();
+        assert_eq!(w.max_faces(), None);
+        let w = Notation::parse("wwI").unwrap();
+        assert_eq!(w.max_faces(), Some(80 + 2 * 210));
+
+        for s in [
+            "", "I", "x", "tX", "a5I", "k", "Ik", "k-1I", "k0I", "t0I", "k2I", "t2I",
+        ] {
+            assert_eq!(Notation::parse(s).is_some(), s == "I", "{s}");
+        }
+
+        // The truncated icosahedron, exactly: pentagons and regular hexagons, all edges the same.
+        let m: Mesh<Vec3<Val>> = Notation::parse("tI").unwrap().mesh();
+        assert_eq!(counts(&m), (60, 90, 32));
+        let sides: Vec<usize> = m.faces().map(|f| m.face_vertices(f).len()).collect();
+        assert_eq!(sides.iter().filter(|&&s| s == 5).count(), 12);
+        assert_eq!(sides.iter().filter(|&&s| s == 6).count(), 20);
+        let length = |h: usize| {
+            let (a, b) = (m.position(m.half_edge(h).origin), m.position(m.dest(h)));
+            b.sub(a).norm().to_f64().unwrap()
+        };
+        let first = length(0);
+        for h in m.edges() {
+            assert_relative_eq!(length(h), first, epsilon = 1e-12);
+        }
+    }
+}
@@ -1,3 +1,5 @@
+use crate::solid::Solid;
+use crate::spherical::Norm;
 use crate::val::{Angle, Val};
 
 /// Angle at the origin between the midpoint of a face and one of its vertices.
@@ -13,3 +15,38 @@ pub fn beta() -> Angle {
         .div(&Val::from(5).sqrt().add(&1.into()))
         .asin()
 }
+
+/// Angle at the origin between two vertices of an edge.
+fn edge() -> Angle {
+    // acos(sqrt(5) / 3)
+    Val::from(5).sqrt().div(&3.into()).acos()
+}
+
+/// Dodecahedron inscribed in the unit sphere, dual to `icosahedron()`:
+/// vertex `i` is the centre of its face `i`, and face `j` surrounds its vertex `j`.
+/// Vertices form four rings of five, from the top.
+pub fn dodecahedron() -> Solid {
+    let top = Norm::zero();
+    let (fifth, tenth) = (Angle::part(5), Angle::part(10));
+    let half = Angle::part(2);
+    let ring = |theta: &Angle, phi: &Angle| {
+        (0..5)
+            .map(|i| top.south(theta).east(&phi.add(&fifth.imul(i))))
+            .collect::<Vec<_>>()
+    };
+    let vertices = [
+        ring(&beta(), &tenth),
+        ring(&beta().add(&edge()), &tenth),
+        ring(&half.sub(&beta()).sub(&edge()), &fifth),
+        ring(&half.sub(&beta()), &fifth),
+    ]
+    .concat();
+
+    // Index in ring r, wrapping around.
+    let v = |r: usize, i: usize| 5 * r + (i + 5) % 5;
+    let mut faces = vec![(0..5).map(|i| v(0, i)).collect()];
+    faces.extend((0..5).map(|i| vec![v(1, i), v(0, i), v(0, i + 4), v(1, i + 4), v(2, i + 4)]));
+    faces.extend((0..5).map(|i| vec![v(2, i), v(1, i), v(2, i + 4), v(3, i + 4), v(3, i)]));
+    faces.push((0..5).rev().map(|i| v(3, i)).collect());
+    Solid::new(vertices, faces)
+}
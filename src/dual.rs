@@ -0,0 +1,197 @@
+use std::cmp::Ordering;
+use std::f64::consts::TAU;
+
+use num_traits::ToPrimitive;
+
+use crate::scalar::{cmp_rounded, Scalar, ScalarAngle};
+use crate::val::{Angle, Val};
+
+/// Dual number v + dε with ε² = 0, for forward-mode derivatives.
+/// Seeding one input with `var` yields the derivative of the result with respect to it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Dual {
+    v: f64,
+    d: f64,
+}
+
+impl Dual {
+    pub fn new(v: f64, d: f64) -> Self {
+        Self { v, d }
+    }
+
+    /// The input being differentiated by.
+    pub fn var(v: f64) -> Self {
+        Self::new(v, 1.0)
+    }
+
+    pub fn constant(v: f64) -> Self {
+        Self::new(v, 0.0)
+    }
+
+    /// The value.
+    pub fn v(&self) -> f64 {
+        self.v
+    }
+
+    /// The derivative.
+    pub fn d(&self) -> f64 {
+        self.d
+    }
+
+    /// Applies a function, given its value and derivative at `v`.
+    fn chain(&self, f: f64, df: f64) -> Self {
+        Self::new(f, df * self.d)
+    }
+}
+
+impl Scalar for Dual {
+    type Angle = Dual;
+
+    fn int(a: i64) -> Self {
+        Self::constant(a as f64)
+    }
+
+    /// NaN for values that can't be evaluated, e.g. parameters.
+    fn from_val(a: &Val) -> Self {
+        Self::constant(a.to_f64().unwrap_or(f64::NAN))
+    }
+
+    fn add(&self, a: &Self) -> Self {
+        Self::new(self.v + a.v, self.d + a.d)
+    }
+
+    fn sub(&self, a: &Self) -> Self {
+        Self::new(self.v - a.v, self.d - a.d)
+    }
+
+    fn neg(&self) -> Self {
+        Self::new(-self.v, -self.d)
+    }
+
+    fn mul(&self, a: &Self) -> Self {
+        Self::new(self.v * a.v, self.d * a.v + self.v * a.d)
+    }
+
+    fn div(&self, a: &Self) -> Self {
+        Self::new(self.v / a.v, (self.d * a.v - self.v * a.d) / (a.v * a.v))
+    }
+
+    fn sqrt(&self) -> Self {
+        let r = self.v.sqrt();
+        self.chain(r, 0.5 / r)
+    }
+
+    fn cbrt(&self) -> Self {
+        let r = self.v.cbrt();
+        self.chain(r, 1.0 / (3.0 * r * r))
+    }
+
+    fn ipow(&self, a: i64) -> Self {
+        let n = a as i32;
+        self.chain(self.v.powi(n), a as f64 * self.v.powi(n - 1))
+    }
+
+    fn asin(&self) -> Self {
+        self.chain(self.v.asin(), 1.0 / (1.0 - self.v * self.v).sqrt())
+    }
+
+    fn acos(&self) -> Self {
+        self.chain(self.v.acos(), -1.0 / (1.0 - self.v * self.v).sqrt())
+    }
+
+    fn atan(&self) -> Self {
+        self.chain(self.v.atan(), 1.0 / (1.0 + self.v * self.v))
+    }
+
+    fn atan2(&self, x: &Self) -> Self {
+        let r2 = x.v * x.v + self.v * self.v;
+        Self::new(self.v.atan2(x.v), (x.v * self.d - self.v * x.d) / r2)
+    }
+
+    fn is_zero(&self) -> bool {
+        self.v == 0.0 && self.d == 0.0
+    }
+}
+
+impl ScalarAngle<Dual> for Dual {
+    fn zero() -> Self {
+        Self::constant(0.0)
+    }
+
+    fn turn() -> Self {
+        Self::constant(TAU)
+    }
+
+    fn from_angle(a: &Angle) -> Self {
+        Self::constant(a.to_f64().unwrap_or(f64::NAN))
+    }
+
+    fn add(&self, a: &Self) -> Self {
+        Scalar::add(self, a)
+    }
+
+    fn sub(&self, a: &Self) -> Self {
+        Scalar::sub(self, a)
+    }
+
+    fn neg(&self) -> Self {
+        Scalar::neg(self)
+    }
+
+    fn mul(&self, a: &Dual) -> Self {
+        Scalar::mul(self, a)
+    }
+
+    fn imul(&self, a: i64) -> Self {
+        Scalar::mul(self, &Self::int(a))
+    }
+
+    fn idiv(&self, a: i64) -> Self {
+        Scalar::div(self, &Self::int(a))
+    }
+
+    fn sin(&self) -> Dual {
+        self.chain(self.v.sin(), self.v.cos())
+    }
+
+    fn cos(&self) -> Dual {
+        self.chain(self.v.cos(), -self.v.sin())
+    }
+
+    fn is_zero(&self) -> bool {
+        Scalar::is_zero(self)
+    }
+
+    /// Shifts the value by whole turns; the derivative is unchanged.
+    fn reduce(&self) -> Self {
+        Self::new(self.v.rem_euclid(TAU), self.d)
+    }
+
+    fn cmp_certified(&self, a: &Self) -> Option<Ordering> {
+        cmp_rounded(self.v, a.v)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_derivatives() {
+        // d/dx atan2(x², 1 - x) at x = 0.5.
+        let x = Dual::var(0.5);
+        let y = x.ipow(2).atan2(&Scalar::sub(&Dual::int(1), &x));
+        let f = |x: f64| (x * x).atan2(1.0 - x);
+        let h = 1e-6;
+        assert_relative_eq!(y.v(), f(0.5));
+        assert_relative_eq!(y.d(), (f(0.5 + h) - f(0.5 - h)) / (2.0 * h), epsilon = 1e-8);
+
+        let s = Scalar::mul(&ScalarAngle::sin(&x), &x.sqrt());
+        assert_relative_eq!(
+            s.d(),
+            0.5_f64.cos() * 0.5_f64.sqrt() + 0.5_f64.sin() * 0.5 / 0.5_f64.sqrt(),
+            epsilon = 1e-15
+        );
+    }
+}
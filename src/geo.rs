@@ -0,0 +1,247 @@
+use std::f64::consts::PI;
+
+use num_traits::ToPrimitive;
+
+use crate::quat::Quat;
+use crate::spherical::Norm;
+use crate::val::{Angle, Val};
+use crate::vec3::Vec3;
+
+/// Placement of the model (e.g. an icosahedron) on the globe.
+/// Geographic coordinates use the north pole as the z axis and the prime meridian in the xz plane.
+#[derive(Clone)]
+pub struct Globe {
+    /// Rotation from geographic to model coordinates.
+    to_model: Quat<Val>,
+}
+
+impl Globe {
+    /// The model's north pole and prime meridian are the geographic ones.
+    pub fn new() -> Self {
+        Self {
+            to_model: Quat::one(),
+        }
+    }
+
+    /// Places the model's initial meridian (phi = 0) at longitude `lon` (degrees east).
+    pub fn prime_meridian(self, lon: &Val) -> Self {
+        Self {
+            to_model: Quat::rot_z(&Angle::from_degrees(lon).neg()).compose(&self.to_model),
+        }
+    }
+
+    /// Places the model's north pole at `top`, and its initial meridian through `meridian`,
+    /// both given in geographic coordinates.
+    pub fn from_vertices(top: &Norm, meridian: &Norm) -> Self {
+        // Bring the pole to `top`, then turn about it until the meridian lines up.
+        let q = rotation_between(&Norm::zero(), top);
+        let m = q.apply(&Norm::zero().south(&Norm::zero().angle_to(meridian)));
+        let (a, p, b) = (Vec3::from(top), Vec3::from(&m), Vec3::from(meridian));
+        let by = a
+            .dot(&p.cross(&b))
+            .atan2(&p.dot(&b).sub(&a.dot(&p).mul(&a.dot(&b))));
+        let to_geo = q.compose(&Quat::from_axis_angle(top, &by));
+        Self {
+            to_model: to_geo.conj(),
+        }
+    }
+
+    /// Buckminster Fuller's Dymaxion orientation of the icosahedron,
+    /// with only two vertices on land and the top one in the North Atlantic.
+    pub fn fuller() -> Self {
+        let v = |x: f64, y: f64, z: f64| Vec3::new(x.into(), y.into(), z.into()).to_norm();
+        Self::from_vertices(
+            // 64.7°N 10.536°E
+            &v(0.42015242670871, 0.07814524940278296, 0.9040825506150193),
+            // 2.301°N 5.245°W
+            &v(
+                0.9950094394362416,
+                -0.09134779527642793,
+                0.04014717587716665,
+            ),
+        )
+    }
+
+    /// Model point at latitude `lat` (degrees north) and longitude `lon` (degrees east).
+    pub fn from_lat_lon(&self, lat: &Val, lon: &Val) -> Norm {
+        let n = Norm::zero()
+            .south(&Angle::from_degrees(&Val::from(90).sub(lat)))
+            .east(&Angle::from_degrees(lon));
+        self.to_model(&n)
+    }
+
+    /// Latitude in [-pi/2, pi/2] and longitude in (-pi, pi] of a model point.
+    pub fn to_lat_lon(&self, n: &Norm) -> (Angle, Angle) {
+        let g = self.to_geo(n);
+        (
+            Angle::turn().idiv(4).sub(g.theta()),
+            g.phi().reduce_signed(),
+        )
+    }
+
+    /// Latitude and longitude of a model point, in degrees.
+    pub fn to_degrees(&self, n: &Norm) -> Option<(f64, f64)> {
+        let (lat, lon) = self.to_lat_lon(n);
+        let lon = lon.to_degrees()?;
+        Some((
+            lat.to_degrees()?,
+            if lon > 180.0 { lon - 360.0 } else { lon },
+        ))
+    }
+
+    /// Local east, north and up unit vectors at a model point, in model coordinates.
+    /// On the poles, north points along the prime meridian and east is a quarter turn from it.
+    pub fn enu(&self, n: &Norm) -> [Vec3<Val>; 3] {
+        self.to_geo(n)
+            .enu()
+            .map(|v| self.to_model.apply_vec(&v).simplify_trig())
+    }
+
+    fn is_identity(&self) -> bool {
+        self.to_model == Quat::one()
+    }
+
+    fn to_model(&self, n: &Norm) -> Norm {
+        if self.is_identity() {
+            n.clone()
+        } else {
+            self.to_model.apply(n)
+        }
+    }
+
+    fn to_geo(&self, n: &Norm) -> Norm {
+        if self.is_identity() {
+            n.clone()
+        } else {
+            self.to_model.conj().apply(n)
+        }
+    }
+}
+
+impl Default for Globe {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Norm {
+    /// Local east, north and up unit vectors, with respect to the z axis.
+    pub fn enu(&self) -> [Vec3<Val>; 3] {
+        let (theta, phi) = (self.theta(), self.phi());
+        [
+            Vec3::new(phi.sin().neg(), phi.cos(), 0.into()),
+            Vec3::new(
+                theta.cos().mul(&phi.cos()).neg(),
+                theta.cos().mul(&phi.sin()).neg(),
+                theta.sin(),
+            ),
+            Vec3::from(self),
+        ]
+    }
+}
+
+/// Shortest rotation taking `a` to `b`; a half turn about some axis if they are antipodal.
+fn rotation_between(a: &Norm, b: &Norm) -> Quat<Val> {
+    let is_zero = |v: &Vec3<Val>| [&v.x, &v.y, &v.z].iter().all(|c| c.is_zero());
+    let axis = Vec3::from(a).cross(&b.into()).simplify_trig();
+    if !is_zero(&axis) {
+        return Quat::from_axis_angle(&axis.to_norm(), &a.angle_to(b));
+    }
+    // Either the same point, or opposite ones; the angle is 0 or pi, so floats tell them apart.
+    let angle = a.angle_to(b);
+    if angle.to_f64().is_some_and(|x| x < PI / 2.0) {
+        return Quat::one();
+    }
+    // Any axis perpendicular to `a` will do; one of the x and y axes is not parallel to it.
+    let axis = [
+        Vec3::new(1.into(), 0.into(), 0.into()),
+        Vec3::new(0.into(), 1.into(), 0.into()),
+    ]
+    .iter()
+    .map(|e| Vec3::from(a).cross(e).simplify_trig())
+    .find(|v| !is_zero(v))
+    .expect("a unit vector is not parallel to both axes")
+    .normalise();
+    // cos(pi/2) + sin(pi/2) (xi + yj + zk)
+    Quat::new(0.into(), axis.x, axis.y, axis.z)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::alpha;
+    use crate::testing::{assert_close, to_f64};
+    use approx::assert_relative_eq;
+    use num_traits::ToPrimitive;
+
+    fn assert_degrees(g: &Globe, n: &Norm, lat: f64, lon: f64) {
+        let (x, y) = g.to_degrees(n).unwrap();
+        // Published values are given to six decimal places.
+        assert_relative_eq!(x, lat, epsilon = 1e-6);
+        assert_relative_eq!(y, lon, epsilon = 1e-6);
+    }
+
+    #[test]
+    fn test_identity() {
+        let g = Globe::new();
+        let n = g.from_lat_lon(&(-30).into(), &(-120).into());
+        assert_eq!(n.theta().to_degrees(), Some(120.0));
+        assert_eq!(n.phi().to_degrees(), Some(240.0));
+        let (lat, lon) = g.to_lat_lon(&n);
+        assert_eq!(lat.to_degrees(), Some(-30.0));
+        assert_eq!(lon.to_degrees(), Some(-120.0));
+    }
+
+    #[test]
+    fn test_prime_meridian() {
+        let g = Globe::new().prime_meridian(&30.into());
+        let n = g.from_lat_lon(&45.into(), &30.into());
+        assert_relative_eq!(n.y().to_f64().unwrap(), 0.0, epsilon = 1e-15);
+        assert_relative_eq!(n.theta().to_degrees().unwrap(), 45.0, epsilon = 1e-12);
+        assert_degrees(&g, &Norm::zero().south(&Angle::part(4)), 0.0, 30.0);
+        assert_degrees(
+            &g,
+            &Norm::zero().south(&Angle::part(4)).west(&Angle::part(4)),
+            0.0,
+            -60.0,
+        );
+    }
+
+    #[test]
+    fn test_fuller() {
+        let g = Globe::fuller();
+        let top = Norm::zero();
+        let v = |i: i64| top.south(&alpha()).east(&Angle::part(5).imul(i));
+        assert_degrees(&g, &top, 64.7, 10.536199);
+        assert_degrees(&g, &v(0), 2.300882, -5.245390);
+        // Round trip.
+        let n = g.from_lat_lon(&Val::from(51.4769), &Val::from(-0.0005));
+        assert_degrees(&g, &n, 51.4769, -0.0005);
+    }
+
+    #[test]
+    fn test_antipodal_top() {
+        let f = |n: &Norm| to_f64(&Vec3::from(n));
+        // The model's north pole at the geographic south pole, with the initial meridian at 90°E.
+        let south = Norm::zero().south(&Angle::part(2));
+        let q = rotation_between(&Norm::zero(), &south);
+        assert_close(&f(&q.apply(&Norm::zero())), &Vec3::new(0.0, 0.0, -1.0));
+        let meridian = Norm::zero().south(&Angle::part(4)).east(&Angle::part(4));
+        let g = Globe::from_vertices(&south, &meridian);
+        assert_close(&f(&g.to_model(&south)), &Vec3::new(0.0, 0.0, 1.0));
+        assert_close(&f(&g.to_model(&meridian)), &Vec3::new(1.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn test_enu() {
+        let g = Globe::new().prime_meridian(&90.into());
+        // 0°N 0°E is a quarter turn west of the initial meridian of the model.
+        let n = g.from_lat_lon(&0.into(), &0.into());
+        let [e, north, up] = g.enu(&n);
+        assert_close(&to_f64(&up), &to_f64(&Vec3::from(&n)));
+        assert_close(&to_f64(&up), &Vec3::new(0.0, -1.0, 0.0));
+        assert_close(&to_f64(&e), &Vec3::new(1.0, 0.0, 0.0));
+        assert_close(&to_f64(&north), &Vec3::new(0.0, 0.0, 1.0));
+        assert_close(&to_f64(&e.cross(&north)), &to_f64(&up));
+    }
+}
@@ -0,0 +1,350 @@
+use std::collections::HashMap;
+
+use crate::icos::icosahedron;
+use crate::mesh::Mesh;
+use crate::projection::Projection;
+use crate::scalar::Scalar;
+use crate::vec3::Vec3;
+
+/// Classes of geodesic subdivision, by the direction of the lattice vector (m, n).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Class {
+    /// (m, 0) or (0, n): the lattice runs along the icosahedron's edges.
+    I,
+    /// (m, m): the lattice runs across the edges, at right angles.
+    II,
+    /// Anything else; chiral, with (n, m) the mirror image of (m, n).
+    III,
+}
+
+/// Geodesic polyhedron {3,5+}_{m,n}, with its vertices on the unit sphere.
+///
+/// Each face of the icosahedron is covered by the triangular lattice,
+/// with its corners at the lattice points 0, (m, n) and (m, n) turned by 60°.
+/// The lattice triangles are then placed on the sphere, see `Projection`.
+#[derive(Clone, Debug)]
+pub struct Geodesic<S: Scalar> {
+    m: usize,
+    n: usize,
+    mesh: Mesh<Vec3<S>>,
+    /// The icosahedron face each triangle came from.
+    parents: Vec<usize>,
+}
+
+impl<S: Scalar> Geodesic<S> {
+    /// Subdivides the flat faces, see `Projection::Flat`.
+    /// Returns `None` for {3,5+}_{0,0}, which has no faces.
+    pub fn new(m: usize, n: usize) -> Option<Self> {
+        Self::with_projection(m, n, Projection::Flat)
+    }
+
+    /// The same subdivision, with the points placed on the sphere in another way.
+    pub fn with_projection(m: usize, n: usize, projection: Projection) -> Option<Self> {
+        if m + n == 0 {
+            return None;
+        }
+        let (mesh, parents) = subdivide(m as i64, n as i64, projection);
+        Some(Self {
+            m,
+            n,
+            mesh,
+            parents,
+        })
+    }
+
+    pub fn m(&self) -> usize {
+        self.m
+    }
+
+    pub fn n(&self) -> usize {
+        self.n
+    }
+
+    pub fn class(&self) -> Class {
+        if self.m == 0 || self.n == 0 {
+            Class::I
+        } else if self.m == self.n {
+            Class::II
+        } else {
+            Class::III
+        }
+    }
+
+    /// Frequency ν = m + n: the number of lattice steps along the path between neighbouring pentavalent vertices.
+    pub fn frequency(&self) -> usize {
+        self.m + self.n
+    }
+
+    /// Triangulation number T = m² + mn + n²; each icosahedron face has the area of T triangles.
+    pub fn t(&self) -> usize {
+        self.m * self.m + self.m * self.n + self.n * self.n
+    }
+
+    /// Number of triangles, 20T.
+    pub fn triangle_count(&self) -> usize {
+        self.mesh.face_count()
+    }
+
+    pub fn mesh(&self) -> &Mesh<Vec3<S>> {
+        &self.mesh
+    }
+
+    /// Number of triangles around a vertex: 5 at the icosahedron's vertices, 6 elsewhere.
+    pub fn valence(&self, v: usize) -> usize {
+        self.mesh.vertex_half_edges(v).len()
+    }
+
+    pub fn valences(&self) -> Vec<usize> {
+        self.mesh.vertices().map(|v| self.valence(v)).collect()
+    }
+
+    /// The icosahedron face a triangle came from, as indexed by `icosahedron()`.
+    /// A triangle whose centre lies in the face is assigned to it;
+    /// those cut in half by an edge go to the face where the edge runs from the lower vertex index.
+    /// Halved triangles occur whenever m ≡ n (mod 3): in class II, and in class III for e.g. (1, 4),
+    /// so then faces get T triangles only on average.
+    pub fn parent(&self, f: usize) -> usize {
+        self.parents[f]
+    }
+
+    pub fn parents(&self) -> &[usize] {
+        &self.parents
+    }
+}
+
+/// Smallest and largest of a measure over the mesh, e.g. edge lengths.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Spread {
+    pub min: f64,
+    pub max: f64,
+}
+
+impl Spread {
+    fn of(values: impl Iterator<Item = f64>) -> Self {
+        values.fold(
+            Self {
+                min: f64::INFINITY,
+                max: f64::NEG_INFINITY,
+            },
+            |s, v| Self {
+                min: s.min.min(v),
+                max: s.max.max(v),
+            },
+        )
+    }
+
+    /// Largest over smallest; 1 when all are the same.
+    pub fn ratio(&self) -> f64 {
+        self.max / self.min
+    }
+}
+
+impl Geodesic<f64> {
+    /// Arc length of each edge, in the order of `Mesh::edges`.
+    pub fn edge_lengths(&self) -> Vec<f64> {
+        self.mesh
+            .edges()
+            .map(|h| {
+                let (a, b) = (
+                    self.mesh.position(self.mesh.half_edge(h).origin),
+                    self.mesh.position(self.mesh.dest(h)),
+                );
+                a.cross(b).norm().atan2(a.dot(b))
+            })
+            .collect()
+    }
+
+    /// Area of each spherical triangle, i.e. its spherical excess.
+    pub fn areas(&self) -> Vec<f64> {
+        self.mesh
+            .faces()
+            .map(|f| {
+                let [a, b, c] =
+                    [0, 1, 2].map(|i| self.mesh.position(self.mesh.face_vertices(f)[i]));
+                // Van Oosterom and Strackee: tan(E / 2) = a · (b × c) / (1 + a · b + b · c + c · a).
+                let num = a.dot(&b.cross(c));
+                let den = 1.0 + a.dot(b) + b.dot(c) + c.dot(a);
+                2.0 * num.atan2(den)
+            })
+            .collect()
+    }
+
+    pub fn edge_spread(&self) -> Spread {
+        Spread::of(self.edge_lengths().into_iter())
+    }
+
+    pub fn area_spread(&self) -> Spread {
+        Spread::of(self.areas().into_iter())
+    }
+}
+
+/// Cross product of lattice vectors, in units of the unit triangle's double area.
+fn cross(a: [i64; 2], b: [i64; 2]) -> i64 {
+    a[0] * b[1] - a[1] * b[0]
+}
+
+fn sub(a: [i64; 2], b: [i64; 2]) -> [i64; 2] {
+    [a[0] - b[0], a[1] - b[1]]
+}
+
+/// Barycentric coordinates of a lattice point, times the area of the big triangle.
+fn barycentric(corners: &[[i64; 2]; 3], p: [i64; 2]) -> [i64; 3] {
+    [0, 1, 2].map(|k| cross(sub(corners[(k + 1) % 3], p), sub(corners[(k + 2) % 3], p)))
+}
+
+/// The lattice triangles on the icosahedron, projected from the centre, with their parent faces.
+fn subdivide<S: Scalar>(m: i64, n: i64, projection: Projection) -> (Mesh<Vec3<S>>, Vec<usize>) {
+    let ico = icosahedron();
+    let vecs: Vec<Vec3<S>> = ico
+        .vertices()
+        .iter()
+        .map(|v| Vec3::from(v.to_scalar::<S>()))
+        .collect();
+    let corners = [[0, 0], [m, n], [-n, m + n]];
+    let t = m * m + m * n + n * n;
+
+    let mut positions = vec![];
+    let mut index = HashMap::new();
+    let mut faces = vec![];
+    let mut parents = vec![];
+    for (f, face) in ico.faces().iter().enumerate() {
+        let across: Vec<usize> = ico
+            .mesh()
+            .face_neighbours(f)
+            .into_iter()
+            .map(|g| g.expect("closed surface"))
+            .collect();
+        // Finds or adds the vertex at a lattice point, which may lie in a neighbouring face.
+        let mut vertex = |p: [i64; 2]| {
+            let l = barycentric(&corners, p);
+            // The face the point is in, and its barycentric coordinates there.
+            let (corners, l) = match (0..3).find(|&k| l[k] < 0) {
+                None => (face.clone(), l.to_vec()),
+                Some(k) => {
+                    // Unfolded, the far corner of the neighbour is the sum of the shared ones minus ours.
+                    let (a, b) = (face[(k + 1) % 3], face[(k + 2) % 3]);
+                    let g = &ico.faces()[across[(k + 1) % 3]];
+                    let l = g
+                        .iter()
+                        .map(|&v| match v {
+                            v if v == a => l[(k + 1) % 3] + l[k],
+                            v if v == b => l[(k + 2) % 3] + l[k],
+                            _ => -l[k],
+                        })
+                        .collect();
+                    (g.clone(), l)
+                }
+            };
+            let mut key: Vec<(usize, i64)> =
+                corners.iter().copied().zip(l.iter().copied()).collect();
+            key.retain(|&(_, l)| l != 0);
+            key.sort();
+            *index.entry(key).or_insert_with(|| {
+                let face = [0, 1, 2].map(|k| vecs[corners[k]].clone());
+                positions.push(projection.point(&face, [l[0], l[1], l[2]], t));
+                positions.len() - 1
+            })
+        };
+
+        for i in -n - 1..=m {
+            for j in -1..=m + n {
+                let up = [[i, j], [i + 1, j], [i, j + 1]];
+                let down = [[i + 1, j], [i + 1, j + 1], [i, j + 1]];
+                for (tri, c) in [(up, 1), (down, 2)] {
+                    // The centroid, scaled by 3 to stay on the lattice.
+                    let l = barycentric(&corners.map(|p| p.map(|x| 3 * x)), [3 * i + c, 3 * j + c]);
+                    // Triangles cut in half by an edge go to the face where the edge runs upwards.
+                    let inside = (0..3)
+                        .all(|k| l[k] > 0 || (l[k] == 0 && face[(k + 1) % 3] < face[(k + 2) % 3]));
+                    if inside {
+                        faces.push(tri.map(&mut vertex).to_vec());
+                        parents.push(f);
+                    }
+                }
+            }
+        }
+    }
+    let mesh = Mesh::from_faces(positions, &faces).expect("consistent winding");
+    (mesh, parents)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::testing::assert_agree;
+    use crate::val::Val;
+
+    #[test]
+    fn test_classes() {
+        let solid = icosahedron();
+        let ico: Vec<Vec3<f64>> = solid
+            .vertices()
+            .iter()
+            .map(|v| Vec3::from(v.to_scalar::<f64>()))
+            .collect();
+        let table = [
+            // (m, n), class, frequency, and per face: triangles fully inside, and cut in half by an edge.
+            ((1, 0), Class::I, 1, 1, 0),
+            ((3, 0), Class::I, 3, 9, 0),
+            ((0, 2), Class::I, 2, 4, 0),
+            ((1, 1), Class::II, 2, 0, 6),
+            ((2, 2), Class::II, 4, 6, 12),
+            ((2, 1), Class::III, 3, 7, 0),
+            ((1, 3), Class::III, 4, 13, 0),
+            ((1, 4), Class::III, 5, 18, 6),
+            ((2, 5), Class::III, 7, 36, 6),
+        ];
+        for ((m, n), class, frequency, inside, halved) in table {
+            let g = Geodesic::<f64>::new(m, n).unwrap();
+            let t = g.t();
+            assert_eq!(g.class(), class);
+            assert_eq!(g.frequency(), frequency);
+            assert_eq!(g.triangle_count(), 20 * t);
+            assert_eq!(g.mesh().vertex_count(), 10 * t + 2);
+            assert_eq!(g.mesh().euler(), 2);
+
+            let valences = g.valences();
+            assert_eq!(valences.iter().filter(|&&k| k == 5).count(), 12);
+            assert_eq!(valences.iter().filter(|&&k| k == 6).count(), 10 * (t - 1));
+
+            // Each triangle's centre is on the inner side of its parent face's edges.
+            // In class III, the centre of a halved triangle moves off the edge when projected,
+            // so there it only has to straddle the edge.
+            let mut count = [0; 20];
+            for f in g.mesh().faces() {
+                let p = g.parent(f);
+                count[p] += 1;
+                let v = g.mesh().face_vertices(f);
+                let centre = v
+                    .iter()
+                    .fold(Vec3::zero(), |c, &v| c.add(g.mesh().position(v)));
+                let face = &solid.faces()[p];
+                for i in 0..3 {
+                    let edge = ico[face[i]].cross(&ico[face[(i + 1) % 3]]);
+                    let straddles = v.iter().any(|&v| edge.dot(g.mesh().position(v)) > 1e-12);
+                    assert!(
+                        edge.dot(&centre) > -1e-12
+                            || (class == Class::III && halved > 0 && straddles)
+                    );
+                }
+            }
+            // Each face keeps its own triangles and some of the halved ones; those are split evenly only when there are none.
+            assert_eq!(2 * inside + halved, 2 * t);
+            assert_eq!(count.iter().sum::<usize>(), 20 * t);
+            assert!(count.iter().all(|&c| inside <= c && c <= inside + halved));
+            assert_eq!(count.iter().all(|&c| c == t), halved == 0);
+        }
+        assert!(Geodesic::<f64>::new(0, 0).is_none());
+    }
+
+    #[test]
+    fn test_exact() {
+        // Exact positions, in agreement with the numeric ones.
+        let (a, b) = (
+            Geodesic::<Val>::new(2, 0).unwrap(),
+            Geodesic::<f64>::new(2, 0).unwrap(),
+        );
+        assert_eq!(a.parents(), b.parents());
+        assert_agree(a.mesh().positions(), b.mesh().positions());
+    }
+}
@@ -0,0 +1,170 @@
+use crate::geodesic::Geodesic;
+use crate::mesh::Mesh;
+use crate::scalar::Scalar;
+use crate::vec3::Vec3;
+
+/// The two kinds of faces of a Goldberg polyhedron.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FaceKind {
+    /// One at each vertex of the icosahedron, 12 in total.
+    Pentagon,
+    Hexagon,
+}
+
+/// Goldberg polyhedron GP(m, n), with its vertices on the unit sphere.
+///
+/// Built as the dual of the geodesic sphere {3,5+}_{m,n}, see `Geodesic`.
+/// GP(n, m) is the mirror image of GP(m, n); the two differ, i.e. the polyhedron is chiral, unless m = n or either is 0.
+#[derive(Clone, Debug)]
+pub struct Goldberg<S: Scalar> {
+    m: usize,
+    n: usize,
+    /// Faces are indexed by the geodesic vertex they surround.
+    mesh: Mesh<Vec3<S>>,
+}
+
+impl<S: Scalar> Goldberg<S> {
+    /// Returns `None` for GP(0, 0), which has no faces.
+    pub fn new(m: usize, n: usize) -> Option<Self> {
+        let geodesic = Geodesic::<S>::new(m, n)?;
+        let geodesic = geodesic.mesh();
+        let positions = geodesic
+            .faces()
+            .map(|f| {
+                let v = geodesic.face_vertices(f);
+                let sum = geodesic.position(v[0]).add(geodesic.position(v[1]));
+                sum.add(geodesic.position(v[2])).normalise()
+            })
+            .collect();
+        let faces: Vec<Vec<usize>> = geodesic
+            .vertices()
+            .map(|v| geodesic.vertex_faces(v))
+            .collect();
+        let mesh = Mesh::from_faces(positions, &faces).expect("dual of a closed surface");
+        Some(Self { m, n, mesh })
+    }
+
+    pub fn m(&self) -> usize {
+        self.m
+    }
+
+    pub fn n(&self) -> usize {
+        self.n
+    }
+
+    /// Triangulation number T = m² + mn + n²; there are 10T + 2 faces.
+    pub fn t(&self) -> usize {
+        self.m * self.m + self.m * self.n + self.n * self.n
+    }
+
+    pub fn mesh(&self) -> &Mesh<Vec3<S>> {
+        &self.mesh
+    }
+
+    pub fn kind(&self, f: usize) -> FaceKind {
+        match self.mesh.face_vertices(f).len() {
+            5 => FaceKind::Pentagon,
+            6 => FaceKind::Hexagon,
+            k => unreachable!("face with {k} sides"),
+        }
+    }
+
+    pub fn pentagons(&self) -> Vec<usize> {
+        self.faces_of(FaceKind::Pentagon)
+    }
+
+    pub fn hexagons(&self) -> Vec<usize> {
+        self.faces_of(FaceKind::Hexagon)
+    }
+
+    fn faces_of(&self, kind: FaceKind) -> Vec<usize> {
+        self.mesh
+            .faces()
+            .filter(|&f| self.kind(f) == kind)
+            .collect()
+    }
+
+    /// Whether the polyhedron differs from its mirror image.
+    pub fn is_chiral(&self) -> bool {
+        self.m != self.n && self.m != 0 && self.n != 0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::testing::outward;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_counts() {
+        for (m, n) in [
+            (1, 0),
+            (0, 1),
+            (1, 1),
+            (2, 0),
+            (2, 1),
+            (1, 2),
+            (3, 1),
+            (4, 2),
+        ] {
+            let g = Goldberg::<f64>::new(m, n).unwrap();
+            let t = g.t();
+            let mesh = g.mesh();
+            assert_eq!(
+                (mesh.vertex_count(), mesh.edge_count(), mesh.face_count()),
+                (20 * t, 30 * t, 10 * t + 2)
+            );
+            assert!(mesh.is_closed());
+            assert_eq!(g.pentagons().len(), 12);
+            assert_eq!(g.hexagons().len(), 10 * (t - 1));
+            for v in mesh.vertices() {
+                assert_relative_eq!(mesh.position(v).norm(), 1.0, epsilon = 1e-12);
+                assert_eq!(mesh.vertex_neighbours(v).len(), 3);
+            }
+
+            // Faces wind outwards, and pentagons are not next to each other unless T is small.
+            assert!(outward(mesh));
+            if t > 3 {
+                for f in g.pentagons() {
+                    let n = mesh.face_neighbours(f);
+                    assert!(n
+                        .iter()
+                        .all(|g| g.is_some_and(|g| g != f && mesh.face_vertices(g).len() == 6)));
+                }
+            }
+        }
+        assert!(Goldberg::<f64>::new(0, 0).is_none());
+
+        // The dodecahedron, with its pentagons at the icosahedron's vertices.
+        let g = Goldberg::<f64>::new(1, 0).unwrap();
+        assert!(g.hexagons().is_empty());
+        assert!(!g.is_chiral());
+    }
+
+    #[test]
+    fn test_chirality() {
+        let points = |g: &Goldberg<f64>, flip: f64| -> Vec<Vec3<f64>> {
+            g.mesh()
+                .positions()
+                .iter()
+                .map(|v| Vec3::new(v.x, flip * v.y, v.z))
+                .collect()
+        };
+        // Each point of one set is also in the other.
+        let same = |a: &[Vec3<f64>], b: &[Vec3<f64>]| {
+            a.len() == b.len() && a.iter().all(|p| b.iter().any(|q| p.sub(q).norm() < 1e-9))
+        };
+
+        let (a, b) = (Goldberg::new(2, 1).unwrap(), Goldberg::new(1, 2).unwrap());
+        assert!(a.is_chiral() && b.is_chiral());
+        // Mirrored in the xz plane, which is a symmetry plane of the icosahedron.
+        assert!(same(&points(&a, -1.0), &points(&b, 1.0)));
+        assert!(!same(&points(&a, 1.0), &points(&b, 1.0)));
+
+        // Achiral ones are their own mirror images.
+        let g = Goldberg::new(2, 2).unwrap();
+        assert!(!g.is_chiral());
+        assert!(same(&points(&g, -1.0), &points(&g, 1.0)));
+    }
+}
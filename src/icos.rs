@@ -1,3 +1,5 @@
+use crate::solid::Solid;
+use crate::spherical::Norm;
 use crate::val::{Angle, Val};
 
 /// The golden ratio.
@@ -36,13 +38,38 @@ pub fn mid() -> Val {
     phi().div(&2.into())
 }
 
+/// Icosahedron inscribed in the unit sphere, with vertex 0 on the z axis and vertex 1 on the xz plane.
+/// Vertices go top, upper ring, lower ring, bottom; rings are ordered eastwards.
+/// Faces go top five, then the ten around the equator, then the bottom five.
+pub fn icosahedron() -> Solid {
+    let top = Norm::zero();
+    let fifth = Angle::part(5);
+    let upper = (0..5).map(|i| top.south(&alpha()).east(&fifth.imul(i)));
+    let lower = (0..5).map(|i| {
+        top.south(&Angle::part(2).sub(&alpha()))
+            .east(&fifth.imul(i).add(&Angle::part(10)))
+    });
+    let mut vertices = vec![top.clone()];
+    vertices.extend(upper);
+    vertices.extend(lower);
+    vertices.push(top.south(&Angle::part(2)));
+
+    let (u, l) = (|i: usize| 1 + i % 5, |i: usize| 6 + i % 5);
+    let mut faces = vec![];
+    faces.extend((0..5).map(|i| vec![0, u(i), u(i + 1)]));
+    faces.extend((0..5).map(|i| vec![u(i), l(i), u(i + 1)]));
+    faces.extend((0..5).map(|i| vec![u(i + 1), l(i), l(i + 1)]));
+    faces.extend((0..5).map(|i| vec![11, l(i + 1), l(i)]));
+    Solid::new(vertices, faces)
+}
+
 #[cfg(test)]
+#[allow(clippy::excessive_precision)]
 mod test {
     use super::*;
     use crate::spherical::Norm;
     use approx::assert_relative_eq;
     use num_traits::ToPrimitive;
-    use std::f64::consts::PI;
 
     #[test]
     fn test_phi() {
@@ -66,13 +93,13 @@ mod test {
 
     #[test]
     fn test_alpha() {
-        assert_relative_eq!(alpha().to_f64().unwrap() * 180.0 / PI, 63.43494882292201);
+        assert_relative_eq!(alpha().to_degrees().unwrap(), 63.43494882292201);
     }
 
     #[test]
     fn test_alpha_distance() {
         assert_relative_eq!(
-            Norm::zero()
+            Norm::<Val>::zero()
                 .distance_to(Norm::zero().south(&alpha()))
                 .to_f64()
                 .unwrap(),
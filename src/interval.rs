@@ -0,0 +1,385 @@
+use std::cmp::Ordering;
+use std::f64::consts::{FRAC_PI_2, PI};
+
+use num_traits::ToPrimitive;
+
+use crate::val::{Angle, Cond, Val};
+
+/// Closed interval of reals, with bounds rounded outwards.
+/// Evaluating an expression on intervals yields bounds certified to contain its exact value.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Interval {
+    lo: f64,
+    hi: f64,
+}
+
+impl Interval {
+    pub fn new(lo: f64, hi: f64) -> Self {
+        Self { lo, hi }
+    }
+
+    /// Degenerate interval containing exactly one value.
+    pub fn point(x: f64) -> Self {
+        Self { lo: x, hi: x }
+    }
+
+    /// Interval containing pi.
+    pub fn pi() -> Self {
+        Self::point(PI).widen()
+    }
+
+    pub fn lo(&self) -> f64 {
+        self.lo
+    }
+
+    pub fn hi(&self) -> f64 {
+        self.hi
+    }
+
+    pub fn mid(&self) -> f64 {
+        (self.lo + self.hi) / 2.0
+    }
+
+    pub fn width(&self) -> f64 {
+        self.hi - self.lo
+    }
+
+    pub fn contains(&self, x: f64) -> bool {
+        self.lo <= x && x <= self.hi
+    }
+
+    /// Rounds both bounds outwards by one unit in the last place.
+    fn widen(self) -> Self {
+        Self::new(self.lo.next_down(), self.hi.next_up())
+    }
+
+    /// Rounds both bounds outwards by a few units in the last place,
+    /// to account for library functions that aren't correctly rounded.
+    fn widen_fn(self) -> Self {
+        self.widen().widen().widen().widen()
+    }
+
+    /// Smallest interval containing both.
+    pub fn hull(&self, a: &Self) -> Self {
+        Self::new(self.lo.min(a.lo), self.hi.max(a.hi))
+    }
+
+    /// Certified comparison: only returns an ordering if the intervals don't overlap.
+    pub fn cmp_certified(&self, a: &Self) -> Option<Ordering> {
+        if self.hi < a.lo {
+            Some(Ordering::Less)
+        } else if self.lo > a.hi {
+            Some(Ordering::Greater)
+        } else if self.width() == 0.0 && self == a {
+            Some(Ordering::Equal)
+        } else {
+            None
+        }
+    }
+
+    pub fn add(&self, a: &Self) -> Self {
+        Self::new(self.lo + a.lo, self.hi + a.hi).widen()
+    }
+
+    pub fn sub(&self, a: &Self) -> Self {
+        Self::new(self.lo - a.hi, self.hi - a.lo).widen()
+    }
+
+    pub fn neg(&self) -> Self {
+        Self::new(-self.hi, -self.lo)
+    }
+
+    pub fn mul(&self, a: &Self) -> Self {
+        let p = [
+            self.lo * a.lo,
+            self.lo * a.hi,
+            self.hi * a.lo,
+            self.hi * a.hi,
+        ];
+        Self::new(
+            p.iter().copied().fold(f64::INFINITY, f64::min),
+            p.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+        )
+        .widen()
+    }
+
+    /// Returns `None` if the divisor may be zero.
+    pub fn div(&self, a: &Self) -> Option<Self> {
+        if a.contains(0.0) {
+            None
+        } else {
+            Some(self.mul(&Self::new(1.0 / a.hi, 1.0 / a.lo).widen()))
+        }
+    }
+
+    /// Returns `None` if the interval is entirely negative.
+    pub fn sqrt(&self) -> Option<Self> {
+        (self.hi >= 0.0).then(|| Self::new(self.lo.max(0.0).sqrt(), self.hi.sqrt()).widen())
+    }
+
+    /// Increasing everywhere, so never fails.
+    pub fn cbrt(&self) -> Self {
+        Self::new(self.lo.cbrt(), self.hi.cbrt()).widen()
+    }
+
+    pub fn abs(&self) -> Self {
+        if self.lo >= 0.0 {
+            *self
+        } else if self.hi <= 0.0 {
+            self.neg()
+        } else {
+            Self::new(0.0, self.hi.max(-self.lo))
+        }
+    }
+
+    /// Integer powers are exact in sign; others require a positive base.
+    pub fn pow(&self, a: &Self) -> Option<Self> {
+        if a.width() == 0.0 && a.lo.fract() == 0.0 && a.lo.abs() < 64.0 {
+            let n = a.lo as i32;
+            let mut r = Self::point(1.0);
+            let b = if n % 2 == 0 { self.abs() } else { *self };
+            for _ in 0..n.abs() {
+                r = r.mul(&b);
+            }
+            return if n < 0 {
+                Self::point(1.0).div(&r)
+            } else {
+                Some(r)
+            };
+        }
+        if self.lo <= 0.0 {
+            return None;
+        }
+        Some(self.ln().mul(a).exp())
+    }
+
+    pub fn exp(&self) -> Self {
+        Self::new(self.lo.exp(), self.hi.exp()).widen_fn()
+    }
+
+    /// Assumes a positive interval.
+    pub fn ln(&self) -> Self {
+        Self::new(self.lo.ln(), self.hi.ln()).widen_fn()
+    }
+
+    pub fn sign(&self) -> Self {
+        let s = |x: f64| if x == 0.0 { 0.0 } else { x.signum() };
+        Self::new(s(self.lo), s(self.hi))
+    }
+
+    pub fn min(&self, a: &Self) -> Self {
+        Self::new(self.lo.min(a.lo), self.hi.min(a.hi))
+    }
+
+    pub fn max(&self, a: &Self) -> Self {
+        Self::new(self.lo.max(a.lo), self.hi.max(a.hi))
+    }
+
+    pub fn sin(&self) -> Self {
+        self.sub(&Self::point(FRAC_PI_2).widen()).cos()
+    }
+
+    pub fn cos(&self) -> Self {
+        if self.width().is_nan() || self.width() >= PI {
+            return Self::new(-1.0, 1.0);
+        }
+        let (a, b) = (self.lo.cos(), self.hi.cos());
+        let mut r = Self::new(a.min(b), a.max(b)).widen_fn();
+        // Extrema at k*pi (maxima for even k); padded, as the multiples of pi are inexact.
+        let k = (self.lo / PI).floor();
+        for k in [k, k + 1.0, k + 2.0] {
+            if self.lo - 1e-9 <= k * PI && k * PI <= self.hi + 1e-9 {
+                r = r.hull(&Self::point(if k % 2.0 == 0.0 { 1.0 } else { -1.0 }));
+            }
+        }
+        Self::new(r.lo.max(-1.0), r.hi.min(1.0))
+    }
+
+    /// Returns `None` if cos may be zero within the interval.
+    pub fn tan(&self) -> Option<Self> {
+        self.sin().div(&self.cos())
+    }
+
+    /// Returns `None` if the interval doesn't intersect [-1, 1].
+    pub fn asin(&self) -> Option<Self> {
+        (self.lo <= 1.0 && self.hi >= -1.0)
+            .then(|| Self::new(self.lo.max(-1.0).asin(), self.hi.min(1.0).asin()).widen_fn())
+    }
+
+    /// Returns `None` if the interval doesn't intersect [-1, 1].
+    pub fn acos(&self) -> Option<Self> {
+        (self.lo <= 1.0 && self.hi >= -1.0)
+            .then(|| Self::new(self.hi.min(1.0).acos(), self.lo.max(-1.0).acos()).widen_fn())
+    }
+
+    pub fn atan(&self) -> Self {
+        Self::new(self.lo.atan(), self.hi.atan()).widen_fn()
+    }
+
+    /// Four-quadrant arctangent of `self / x`.
+    /// Returns `None` if the result may jump across the negative x axis.
+    pub fn atan2(&self, x: &Self) -> Option<Self> {
+        if x.contains(0.0) && self.contains(0.0) {
+            return None;
+        }
+        if x.hi < 0.0 && self.contains(0.0) {
+            return None;
+        }
+        let c = [
+            self.lo.atan2(x.lo),
+            self.lo.atan2(x.hi),
+            self.hi.atan2(x.lo),
+            self.hi.atan2(x.hi),
+        ];
+        Some(
+            Self::new(
+                c.iter().copied().fold(f64::INFINITY, f64::min),
+                c.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+            )
+            .widen_fn(),
+        )
+    }
+}
+
+impl Val {
+    /// Evaluates the value on intervals, yielding certified bounds.
+    /// Returns `None` for parameters, or where bounds can't be found (e.g. division by zero).
+    pub fn to_interval(&self) -> Option<Interval> {
+        match self {
+            Self::Int(a) => {
+                let x = a.to_f64()?;
+                // Integers up to 2^53 are exact.
+                if x.abs() <= 9007199254740992.0 {
+                    Some(Interval::point(x))
+                } else {
+                    Some(Interval::point(x).widen())
+                }
+            }
+            Self::Float(a) => Some(Interval::point(*a)),
+            Self::Sum(a, b) => Some(a.to_interval()?.add(&b.to_interval()?)),
+            Self::Dif(a, b) => Some(a.to_interval()?.sub(&b.to_interval()?)),
+            Self::Prd(a, b) => Some(a.to_interval()?.mul(&b.to_interval()?)),
+            Self::Rat(a, b) => a.to_interval()?.div(&b.to_interval()?),
+            Self::Pow(a, b) => a.to_interval()?.pow(&b.to_interval()?),
+            Self::Sqrt(a) => a.to_interval()?.sqrt(),
+            Self::Sin(a) => Some(a.to_interval()?.sin()),
+            Self::Cos(a) => Some(a.to_interval()?.cos()),
+            Self::Tan(a) => a.to_interval()?.tan(),
+            Self::Exp(a) => Some(a.to_interval()?.exp()),
+            Self::Ln(a) => {
+                let x = a.to_interval()?;
+                (x.lo > 0.0).then(|| x.ln())
+            }
+            Self::Abs(a) => Some(a.to_interval()?.abs()),
+            Self::Sign(a) => Some(a.to_interval()?.sign()),
+            Self::Min(a, b) => Some(a.to_interval()?.min(&b.to_interval()?)),
+            Self::Max(a, b) => Some(a.to_interval()?.max(&b.to_interval()?)),
+            Self::If(c, a, b) => match c.to_certified() {
+                Some(true) => a.to_interval(),
+                Some(false) => b.to_interval(),
+                None => Some(a.to_interval()?.hull(&b.to_interval()?)),
+            },
+            Self::Param(_) => None,
+        }
+    }
+}
+
+impl Angle {
+    /// Certified comparison: exact for rational multiples of pi, otherwise using intervals.
+    /// Returns `None` if the order can't be decided, e.g. for parameters or equal irrational angles.
+    pub fn cmp_certified(&self, a: &Self) -> Option<Ordering> {
+        if self == a {
+            return Some(Ordering::Equal);
+        }
+        match (self.to_rational(), a.to_rational()) {
+            (Some(x), Some(y)) => Some(x.cmp(&y)),
+            _ => self.to_interval()?.cmp_certified(&a.to_interval()?),
+        }
+    }
+
+    /// Evaluates the angle (in radians) on intervals, yielding certified bounds.
+    pub fn to_interval(&self) -> Option<Interval> {
+        match self {
+            Self::Pi(a) => Some(a.to_interval()?.mul(&Interval::pi())),
+            Self::Sum(a, b) => Some(a.to_interval()?.add(&b.to_interval()?)),
+            Self::Dif(a, b) => Some(a.to_interval()?.sub(&b.to_interval()?)),
+            Self::Prd(a, b) => Some(a.to_interval()?.mul(&b.to_interval()?)),
+            Self::Rat(a, b) => a.to_interval()?.div(&b.to_interval()?),
+            Self::ASin(a) => a.to_interval()?.asin(),
+            Self::ACos(a) => a.to_interval()?.acos(),
+            Self::ATan(a) => Some(a.to_interval()?.atan()),
+            Self::ATan2(a, b) => a.to_interval()?.atan2(&b.to_interval()?),
+        }
+    }
+}
+
+impl Cond {
+    /// Decides the condition exactly where possible, otherwise using intervals.
+    pub fn to_certified(&self) -> Option<bool> {
+        let cmp = |a: &Val, b: &Val| match (a.to_rational(), b.to_rational()) {
+            (Some(x), Some(y)) => Some(x.cmp(&y)),
+            _ => a.to_interval()?.cmp_certified(&b.to_interval()?),
+        };
+        match self {
+            Self::Lt(a, b) => cmp(a, b).map(|o| o == Ordering::Less),
+            Self::Le(a, b) => cmp(a, b).map(|o| o != Ordering::Greater),
+            Self::Eq(a, b) => match cmp(a, b) {
+                Some(Ordering::Equal) => Some(true),
+                Some(_) => Some(false),
+                None => (a == b).then_some(true),
+            },
+            Self::And(a, b) => match (a.to_certified(), b.to_certified()) {
+                (Some(false), _) | (_, Some(false)) => Some(false),
+                (x, y) => x.and(y),
+            },
+            Self::Or(a, b) => match (a.to_certified(), b.to_certified()) {
+                (Some(true), _) | (_, Some(true)) => Some(true),
+                (x, y) => x.or(y),
+            },
+            Self::Not(a) => a.to_certified().map(|x| !x),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{alpha, beta, phi};
+
+    fn assert_encloses(v: &Val) {
+        let i = v.to_interval().unwrap();
+        let x = v.to_f64().unwrap();
+        assert!(i.contains(x), "{:?} doesn't contain {}", i, x);
+        assert!(i.width() < 1e-12, "{:?} is too wide", i);
+    }
+
+    #[test]
+    fn test_encloses() {
+        assert_encloses(&phi());
+        assert_encloses(&alpha().sin());
+        assert_encloses(&beta().cos());
+        assert_encloses(&beta().tan());
+        assert_encloses(&phi().ln().exp());
+        assert_encloses(&Val::from(-3).idiv(7).atan2(&Val::from(-2)).cos());
+        assert_encloses(&Angle::part(4).cos());
+        assert_encloses(&Angle::part(2).cos());
+    }
+
+    #[test]
+    fn test_cos_extrema() {
+        let i = Interval::new(-0.1, 0.1).cos();
+        assert_eq!(i.hi(), 1.0);
+        let i = Interval::new(PI - 0.1, PI + 0.1).cos();
+        assert_eq!(i.lo(), -1.0);
+        assert_eq!(Interval::new(0.0, 7.0).sin(), Interval::new(-1.0, 1.0));
+    }
+
+    #[test]
+    fn test_certified_cond() {
+        // phi^2 = phi + 1 can't be decided on intervals, but phi^2 < phi + 1.001 can.
+        let sq = phi().ipow(2);
+        assert_eq!(Cond::lt(&sq, &phi().iadd(1)).to_certified(), None);
+        let bound = phi().add(&Val::from(1001).idiv(1000));
+        assert_eq!(Cond::lt(&sq, &bound).to_certified(), Some(true));
+    }
+}
@@ -1,10 +1,53 @@
+mod batch;
+mod circle;
+mod conway;
 mod dodec;
+mod dual;
+mod geo;
+mod geodesic;
+mod goldberg;
 mod icos;
+mod interval;
+mod mesh;
+mod param;
+mod platonic;
+mod polygon;
+mod projection;
+mod quat;
+mod rewrite;
+mod scalar;
+mod solid;
 mod spherical;
+mod stellation;
+#[cfg(test)]
+mod testing;
+mod trig;
 mod val;
+mod vec3;
 pub mod web;
+mod wythoff;
 
-pub use dodec::beta;
-pub use icos::{alpha, cir, inr, mid, phi};
+pub use batch::{icosahedral, Mat3, Points};
+pub use circle::{GreatCircle, SmallCircle};
+pub use conway::{Notation, Op};
+pub use dodec::{beta, dodecahedron};
+pub use dual::Dual;
+pub use geo::Globe;
+pub use geodesic::{Class, Geodesic, Spread};
+pub use goldberg::{FaceKind, Goldberg};
+pub use icos::{alpha, cir, icosahedron, inr, mid, phi};
+pub use interval::Interval;
+pub use mesh::{HalfEdge, Mesh};
+pub use param::Param;
+pub use platonic::Platonic;
+pub use polygon::Polygon;
+pub use projection::Projection;
+pub use quat::Quat;
+pub use rewrite::{AnglePat, Bindings, Guard, Pat, Rewriter, Rule, Strategy};
+pub use scalar::{Scalar, ScalarAngle};
+pub use solid::Solid;
 pub use spherical::Norm;
-pub use val::{Angle, Val};
+pub use stellation::{Diagram, Stellation};
+pub use val::{Angle, Cond, Val};
+pub use vec3::Vec3;
+pub use wythoff::Wythoff;
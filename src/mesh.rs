@@ -0,0 +1,477 @@
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+
+use crate::scalar::Scalar;
+use crate::solid::Solid;
+use crate::spherical::Norm;
+use crate::vec3::Vec3;
+
+/// One side of an edge, belonging to the face on its left.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HalfEdge {
+    /// The vertex it starts from.
+    pub origin: usize,
+    /// The opposite half-edge, or `None` on the boundary.
+    pub twin: Option<usize>,
+    /// The following half-edge around the same face.
+    pub next: usize,
+    /// The preceding half-edge around the same face.
+    pub prev: usize,
+    pub face: usize,
+}
+
+/// Polygon mesh with half-edge connectivity.
+/// Faces go counterclockwise when looking from outside; the surface may have boundaries.
+#[derive(Clone, Debug)]
+pub struct Mesh<P> {
+    positions: Vec<P>,
+    half_edges: Vec<HalfEdge>,
+    /// An outgoing half-edge for each vertex; the one on the boundary for boundary vertices.
+    vertex_edge: Vec<Option<usize>>,
+    /// The first half-edge of each face.
+    face_edge: Vec<usize>,
+}
+
+impl<P> Mesh<P> {
+    /// Builds the connectivity from faces given as vertex indices.
+    /// Returns `None` if a face has fewer than 3 vertices or refers to a missing one,
+    /// or if a directed edge appears twice (i.e. inconsistent winding, or not a manifold).
+    pub fn from_faces(positions: Vec<P>, faces: &[Vec<usize>]) -> Option<Self> {
+        let mut half_edges = vec![];
+        let mut face_edge = vec![];
+        let mut directed = HashMap::new();
+        for (f, face) in faces.iter().enumerate() {
+            let n = face.len();
+            if n < 3 || face.iter().any(|&v| v >= positions.len()) {
+                return None;
+            }
+            let first = half_edges.len();
+            face_edge.push(first);
+            for i in 0..n {
+                let h = first + i;
+                if directed.insert((face[i], face[(i + 1) % n]), h).is_some() {
+                    return None;
+                }
+                half_edges.push(HalfEdge {
+                    origin: face[i],
+                    twin: None,
+                    next: first + (i + 1) % n,
+                    prev: first + (i + n - 1) % n,
+                    face: f,
+                });
+            }
+        }
+
+        let mut vertex_edge = vec![None; positions.len()];
+        for h in 0..half_edges.len() {
+            let (a, b) = (half_edges[h].origin, half_edges[half_edges[h].next].origin);
+            let twin = directed.get(&(b, a)).copied();
+            half_edges[h].twin = twin;
+            // Prefer boundary half-edges, so walks around the vertex can start there.
+            if vertex_edge[a].is_none() || twin.is_none() {
+                vertex_edge[a] = Some(h);
+            }
+        }
+
+        Some(Self {
+            positions,
+            half_edges,
+            vertex_edge,
+            face_edge,
+        })
+    }
+
+    pub fn positions(&self) -> &[P] {
+        &self.positions
+    }
+
+    pub fn position(&self, v: usize) -> &P {
+        &self.positions[v]
+    }
+
+    pub fn half_edges(&self) -> &[HalfEdge] {
+        &self.half_edges
+    }
+
+    pub fn half_edge(&self, h: usize) -> &HalfEdge {
+        &self.half_edges[h]
+    }
+
+    /// The vertex a half-edge points to.
+    pub fn dest(&self, h: usize) -> usize {
+        self.half_edges[self.half_edges[h].next].origin
+    }
+
+    pub fn vertices(&self) -> Range<usize> {
+        0..self.positions.len()
+    }
+
+    pub fn faces(&self) -> Range<usize> {
+        0..self.face_edge.len()
+    }
+
+    /// Each edge once, as a representative half-edge.
+    pub fn edges(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.half_edges.len()).filter(|&h| self.half_edges[h].twin.is_none_or(|t| h < t))
+    }
+
+    pub fn vertex_count(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.edges().count()
+    }
+
+    pub fn face_count(&self) -> usize {
+        self.face_edge.len()
+    }
+
+    /// V - E + F; 2 for closed surfaces of genus 0.
+    pub fn euler(&self) -> i64 {
+        self.vertex_count() as i64 - self.edge_count() as i64 + self.face_count() as i64
+    }
+
+    /// The half-edges around a face, in order.
+    pub fn face_half_edges(&self, f: usize) -> Vec<usize> {
+        let first = self.face_edge[f];
+        let mut out = vec![first];
+        let mut h = self.half_edges[first].next;
+        while h != first {
+            out.push(h);
+            h = self.half_edges[h].next;
+        }
+        out
+    }
+
+    /// The vertices of a face, in order.
+    pub fn face_vertices(&self, f: usize) -> Vec<usize> {
+        self.face_half_edges(f)
+            .into_iter()
+            .map(|h| self.half_edges[h].origin)
+            .collect()
+    }
+
+    /// The faces across each edge of face `f`, in order; `None` on the boundary.
+    pub fn face_neighbours(&self, f: usize) -> Vec<Option<usize>> {
+        self.face_half_edges(f)
+            .into_iter()
+            .map(|h| self.half_edges[h].twin.map(|t| self.half_edges[t].face))
+            .collect()
+    }
+
+    /// The outgoing half-edges of a vertex, counterclockwise when looking from outside.
+    /// On the boundary, starts with the boundary edge that has no face to its right.
+    pub fn vertex_half_edges(&self, v: usize) -> Vec<usize> {
+        let Some(first) = self.vertex_edge[v] else {
+            return vec![];
+        };
+        let mut out = vec![first];
+        // The next outgoing half-edge counterclockwise is the twin of the incoming one before it.
+        let mut h = first;
+        while let Some(t) = self.half_edges[self.half_edges[h].prev].twin {
+            if t == first {
+                break;
+            }
+            out.push(t);
+            h = t;
+        }
+        out
+    }
+
+    /// The vertices joined to `v` by an edge, counterclockwise when looking from outside.
+    pub fn vertex_neighbours(&self, v: usize) -> Vec<usize> {
+        let mut out: Vec<usize> = self
+            .vertex_half_edges(v)
+            .into_iter()
+            .map(|h| self.dest(h))
+            .collect();
+        if self.is_boundary_vertex(v) {
+            // The last face around the vertex ends on the other boundary edge.
+            let last = *self.vertex_half_edges(v).last().unwrap();
+            out.push(self.half_edges[self.half_edges[last].prev].origin);
+        }
+        out
+    }
+
+    /// The faces around a vertex, counterclockwise when looking from outside.
+    pub fn vertex_faces(&self, v: usize) -> Vec<usize> {
+        self.vertex_half_edges(v)
+            .into_iter()
+            .map(|h| self.half_edges[h].face)
+            .collect()
+    }
+
+    pub fn is_boundary_vertex(&self, v: usize) -> bool {
+        self.vertex_edge[v].is_some_and(|h| self.half_edges[h].twin.is_none())
+    }
+
+    /// Checks whether every edge has a face on both sides.
+    pub fn is_closed(&self) -> bool {
+        self.half_edges.iter().all(|h| h.twin.is_some())
+    }
+
+    /// The boundaries, as loops of vertices; they go clockwise around the holes when looking from outside.
+    pub fn boundary_loops(&self) -> Vec<Vec<usize>> {
+        let mut seen = vec![false; self.half_edges.len()];
+        let mut loops = vec![];
+        for start in 0..self.half_edges.len() {
+            if seen[start] || self.half_edges[start].twin.is_some() {
+                continue;
+            }
+            let mut vertices = vec![];
+            let mut h = start;
+            loop {
+                seen[h] = true;
+                vertices.push(self.half_edges[h].origin);
+                // The next boundary half-edge starts where this one ends.
+                h = *self
+                    .vertex_half_edges(self.dest(h))
+                    .first()
+                    .expect("boundary continues");
+                if h == start {
+                    break;
+                }
+            }
+            loops.push(vertices);
+        }
+        loops
+    }
+
+    /// Triangles fanning out from the first vertex of each face; exact for convex faces.
+    pub fn triangles(&self) -> Vec<[usize; 3]> {
+        self.faces()
+            .flat_map(|f| {
+                let v = self.face_vertices(f);
+                (1..v.len() - 1).map(move |i| [v[0], v[i], v[i + 1]])
+            })
+            .collect()
+    }
+
+    /// Triangles as a flat index buffer.
+    pub fn indices(&self) -> Vec<u64> {
+        self.triangles()
+            .into_iter()
+            .flatten()
+            .map(|v| v as u64)
+            .collect()
+    }
+
+    /// The same connectivity with other positions, e.g. projected or approximated.
+    pub fn map<Q>(&self, f: impl Fn(&P) -> Q) -> Mesh<Q> {
+        Mesh {
+            positions: self.positions.iter().map(f).collect(),
+            half_edges: self.half_edges.clone(),
+            vertex_edge: self.vertex_edge.clone(),
+            face_edge: self.face_edge.clone(),
+        }
+    }
+}
+
+impl<S: Scalar> Mesh<Vec3<S>> {
+    /// Distance from the centre to the line through an edge, given as a half-edge.
+    pub fn edge_distance(&self, h: usize) -> S {
+        let (a, b) = (
+            self.position(self.half_edges[h].origin),
+            self.position(self.dest(h)),
+        );
+        a.cross(b).norm().div(&b.sub(a).norm())
+    }
+
+    /// Polar reciprocal about the sphere of radius `r` around the origin.
+    /// Each face becomes the pole of its plane, and each vertex a face, so the dual's face `v` surrounds vertex `v`;
+    /// vertex `f` comes from face `f`. Faces are taken to be planar, through their first three vertices.
+    /// Returns `None` for open meshes, and for faces whose plane passes through the centre.
+    pub fn reciprocal(&self, r: &S) -> Option<Self> {
+        if !self.is_closed() {
+            return None;
+        }
+        let r2 = r.mul(r);
+        let mut poles = vec![];
+        for f in self.faces() {
+            let v = self.face_vertices(f);
+            let [a, b, c] = [0, 1, 2].map(|i| self.position(v[i]));
+            let normal = b.sub(a).cross(&c.sub(a)).simplify();
+            let d = normal.dot(a).simplify();
+            if d.is_zero() {
+                return None;
+            }
+            poles.push(normal.scale(&r2.div(&d)).simplify());
+        }
+        // Faces around a vertex go counterclockwise, so the dual keeps the orientation.
+        let faces: Vec<Vec<usize>> = self.vertices().map(|v| self.vertex_faces(v)).collect();
+        Self::from_faces(poles, &faces)
+    }
+
+    /// Reciprocal about the midsphere, taken to touch the first edge.
+    /// For canonical polyhedra, e.g. the Platonic solids, this gives the canonical dual, with the same midsphere.
+    pub fn dual(&self) -> Option<Self> {
+        if !self.is_closed() {
+            return None;
+        }
+        let h = self.edges().next()?;
+        self.reciprocal(&self.edge_distance(h))
+    }
+}
+
+impl From<&Solid> for Mesh<Norm> {
+    fn from(s: &Solid) -> Self {
+        s.mesh().clone()
+    }
+}
+
+/// Faces of a graph drawn without crossings, from the neighbours of each vertex in counterclockwise order.
+/// The face on the left of u -> v continues to the neighbour of v just clockwise from u;
+/// in the plane, the outer face comes out clockwise.
+pub(crate) fn trace_faces(neighbours: &[Vec<usize>]) -> Vec<Vec<usize>> {
+    let mut seen = HashSet::new();
+    let mut faces = vec![];
+    for v in 0..neighbours.len() {
+        for &u in &neighbours[v] {
+            let mut face = vec![];
+            let (mut u, mut v) = (u, v);
+            while seen.insert((u, v)) {
+                face.push(v);
+                let around = &neighbours[v];
+                let i = around.iter().position(|&w| w == u).expect("neighbours");
+                (u, v) = (v, around[(i + around.len() - 1) % around.len()]);
+            }
+            if !face.is_empty() {
+                faces.push(face);
+            }
+        }
+    }
+    faces
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::testing::to_f64;
+    use crate::val::Val;
+    use crate::{cir, dodecahedron, icosahedron, inr, mid, Geodesic, Goldberg};
+    use approx::assert_relative_eq;
+    use num_traits::ToPrimitive;
+
+    #[test]
+    fn test_solids() {
+        let s = icosahedron();
+        let m = Mesh::from(&s);
+        assert_eq!(
+            (m.vertex_count(), m.edge_count(), m.face_count()),
+            (12, 30, 20)
+        );
+        assert_eq!(m.euler(), 2);
+        assert!(m.is_closed());
+        assert!(m.boundary_loops().is_empty());
+        for v in m.vertices() {
+            assert!(!m.is_boundary_vertex(v));
+            assert_eq!(m.vertex_neighbours(v).len(), 5);
+            // Each face around a vertex follows the previous one across an edge.
+            let around = m.vertex_faces(v);
+            for (i, f) in around.iter().enumerate() {
+                let next = around[(i + 1) % around.len()];
+                assert!(m.face_neighbours(*f).contains(&Some(next)));
+            }
+        }
+        for f in m.faces() {
+            assert_eq!(&m.face_vertices(f), &s.faces()[f]);
+            for g in m.face_neighbours(f) {
+                assert!(m.face_neighbours(g.unwrap()).contains(&Some(f)));
+            }
+        }
+        assert_eq!(m.triangles().len(), 20);
+
+        // Pentagons split into three triangles each.
+        let m = Mesh::from(&dodecahedron());
+        assert_eq!(m.euler(), 2);
+        assert_eq!(m.indices().len(), 12 * 3 * 3);
+        assert_eq!(&m.indices()[..9], &[0, 1, 2, 0, 2, 3, 0, 3, 4]);
+    }
+
+    #[test]
+    fn test_boundary() {
+        // A square split into a triangle and a quad, around vertex 4 in the middle of the bottom edge.
+        //  3 --- 2
+        //  |   / |
+        //  | /   |
+        //  0--4--1
+        let m = Mesh::from_faces(vec![(); 5], &[vec![0, 4, 1, 2], vec![0, 2, 3]]).unwrap();
+        assert_eq!(
+            (m.vertex_count(), m.edge_count(), m.face_count()),
+            (5, 6, 2)
+        );
+        assert_eq!(m.euler(), 1);
+        assert!(!m.is_closed());
+        assert!(m.vertices().all(|v| m.is_boundary_vertex(v)));
+        assert_eq!(m.boundary_loops(), vec![vec![0, 4, 1, 2, 3]]);
+        assert_eq!(m.vertex_neighbours(0), vec![4, 2, 3]);
+        assert_eq!(m.vertex_faces(0), vec![0, 1]);
+        assert_eq!(m.vertex_neighbours(2), vec![3, 0, 1]);
+        assert_eq!(m.face_neighbours(1), vec![Some(0), None, None]);
+        assert_eq!(m.triangles(), vec![[0, 4, 1], [0, 1, 2], [0, 2, 3]]);
+
+        // Inconsistent winding, and degenerate faces.
+        assert!(Mesh::from_faces(vec![(); 4], &[vec![0, 1, 2], vec![0, 1, 3]]).is_none());
+        assert!(Mesh::from_faces(vec![(); 2], &[vec![0, 1]]).is_none());
+    }
+
+    #[test]
+    fn test_dual() {
+        // The icosahedron with unit edges; its dual is the dodecahedron with the same midsphere.
+        let ico = Mesh::from(&icosahedron()).map(|n| Vec3::from(n).scale(&cir()));
+        let h = ico.edges().next().unwrap();
+        assert_relative_eq!(
+            ico.edge_distance(h).to_f64().unwrap(),
+            mid().to_f64().unwrap(),
+            epsilon = 1e-12
+        );
+        let dodec = ico.dual().unwrap();
+        assert_eq!(
+            (dodec.vertex_count(), dodec.edge_count(), dodec.face_count()),
+            (20, 30, 12)
+        );
+        let r = mid().ipow(2).div(&inr()).to_f64().unwrap();
+        for v in dodec.vertices() {
+            assert_relative_eq!(
+                dodec.position(v).norm().to_f64().unwrap(),
+                r,
+                epsilon = 1e-12
+            );
+        }
+        // Same faces as the dodecahedron, with outward winding.
+        for (f, face) in dodecahedron().faces().iter().enumerate() {
+            let mut got = dodec.face_vertices(f);
+            let i = got.iter().position(|&v| v == face[0]).unwrap();
+            got.rotate_left(i);
+            assert_eq!(&got, face);
+        }
+
+        // Back again, about the same sphere.
+        let back = dodec
+            .map(to_f64)
+            .reciprocal(&mid().to_f64().unwrap())
+            .unwrap();
+        for v in ico.vertices() {
+            let d = back.position(v).sub(&to_f64(ico.position(v))).norm();
+            assert_relative_eq!(d, 0.0, epsilon = 1e-12);
+        }
+
+        // Geodesic spheres have planar faces; their duals are Goldberg polyhedra with planar faces.
+        let g = Geodesic::<f64>::new(2, 1).unwrap();
+        let dual = g.mesh().reciprocal(&1.0).unwrap();
+        let gp = Goldberg::<f64>::new(2, 1).unwrap();
+        for f in dual.faces() {
+            assert_eq!(dual.face_vertices(f), gp.mesh().face_vertices(f));
+        }
+        for v in dual.vertices() {
+            // Poles of the triangles, which point the same way as the Goldberg vertices.
+            let (p, q) = (dual.position(v), gp.mesh().position(v));
+            assert!(p.dot(q) > 0.99 * p.norm());
+        }
+
+        // Open meshes have no dual.
+        let open = Mesh::from_faces(vec![Vec3::<Val>::zero(); 3], &[vec![0, 1, 2]]).unwrap();
+        assert!(open.dual().is_none());
+    }
+}
@@ -0,0 +1,114 @@
+use crate::val::Val;
+
+/// Declaration of a parameter referenced by `Val::Param`, shown by the same symbol, e.g. `t_1`.
+#[derive(Clone, Debug)]
+pub struct Param {
+    /// Index, as in `Val::Param(index)`.
+    pub(crate) index: usize,
+
+    /// Human-readable name.
+    pub(crate) name: String,
+
+    /// Initial value, as an expression.
+    pub(crate) default: Val,
+
+    /// Valid range [min-max], and the step to use when sliding through it.
+    pub(crate) min: f64,
+    pub(crate) max: f64,
+    pub(crate) step: f64,
+
+    pub(crate) description: String,
+
+    /// Unit of measure, if any, e.g. "rad".
+    pub(crate) unit: String,
+}
+
+impl Param {
+    /// A parameter ranging over [0-1], defaulting to 0.
+    pub fn new(index: usize, name: &str) -> Self {
+        Self {
+            index,
+            name: name.into(),
+            default: 0.into(),
+            min: 0.0,
+            max: 1.0,
+            step: 0.01,
+            description: String::new(),
+            unit: String::new(),
+        }
+    }
+
+    pub fn default(self, default: &Val) -> Self {
+        Self {
+            default: default.clone(),
+            ..self
+        }
+    }
+
+    pub fn range(self, min: f64, max: f64) -> Self {
+        Self { min, max, ..self }
+    }
+
+    pub fn step(self, step: f64) -> Self {
+        Self { step, ..self }
+    }
+
+    pub fn description(self, description: &str) -> Self {
+        Self {
+            description: description.into(),
+            ..self
+        }
+    }
+
+    pub fn unit(self, unit: &str) -> Self {
+        Self {
+            unit: unit.into(),
+            ..self
+        }
+    }
+
+    /// The value referencing this parameter.
+    pub fn val(&self) -> Val {
+        Val::param(self.index)
+    }
+
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn default_val(&self) -> &Val {
+        &self.default
+    }
+
+    /// The valid range as (min, max).
+    pub fn bounds(&self) -> (f64, f64) {
+        (self.min, self.max)
+    }
+
+    /// Checks if a value is within the valid range.
+    pub fn contains(&self, x: f64) -> bool {
+        x >= self.min && x <= self.max
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_param() {
+        let p = Param::new(2, "Twist")
+            .default(&Val::from(1).idiv(2))
+            .range(-1.0, 1.0)
+            .unit("turn");
+        assert_eq!(p.val(), Val::param(2));
+        assert_eq!(p.name(), "Twist");
+        assert_eq!(p.default_val(), &Val::from(1).idiv(2));
+        assert_eq!(p.bounds(), (-1.0, 1.0));
+        assert!(p.contains(-0.5) && !p.contains(1.5));
+    }
+}
@@ -0,0 +1,377 @@
+use crate::dodec::dodecahedron;
+use crate::icos::{cir, icosahedron, inr, mid, phi};
+use crate::mesh::Mesh;
+use crate::scalar::Scalar;
+use crate::val::{Angle, Val};
+use crate::vec3::Vec3;
+
+/// The five Platonic solids.
+/// Metric properties are exact, for edge length 1.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Platonic {
+    Tetrahedron,
+    Cube,
+    Octahedron,
+    Dodecahedron,
+    Icosahedron,
+}
+
+fn sqrt(a: i64) -> Val {
+    Val::from(a).sqrt()
+}
+
+impl Platonic {
+    pub fn all() -> [Self; 5] {
+        [
+            Self::Tetrahedron,
+            Self::Cube,
+            Self::Octahedron,
+            Self::Dodecahedron,
+            Self::Icosahedron,
+        ]
+    }
+
+    /// Schläfli symbol {p, q}: p-gonal faces, q of them around each vertex.
+    pub fn schlafli(&self) -> (i64, i64) {
+        match self {
+            Self::Tetrahedron => (3, 3),
+            Self::Cube => (4, 3),
+            Self::Octahedron => (3, 4),
+            Self::Dodecahedron => (5, 3),
+            Self::Icosahedron => (3, 5),
+        }
+    }
+
+    /// Number of vertices, edges and faces.
+    pub fn counts(&self) -> (i64, i64, i64) {
+        let (p, q) = self.schlafli();
+        // From Euler's formula, with pF = 2E = qV.
+        let e = 2 * p * q / (2 * (p + q) - p * q);
+        (2 * e / q, e, 2 * e / p)
+    }
+
+    /// The solid with faces and vertices swapped.
+    pub fn dual(&self) -> Self {
+        match self {
+            Self::Tetrahedron => Self::Tetrahedron,
+            Self::Cube => Self::Octahedron,
+            Self::Octahedron => Self::Cube,
+            Self::Dodecahedron => Self::Icosahedron,
+            Self::Icosahedron => Self::Dodecahedron,
+        }
+    }
+
+    /// The solid with its vertices on the unit sphere.
+    /// The icosahedron and dodecahedron are oriented as `icosahedron()` and `dodecahedron()`;
+    /// the others have their vertices, or their faces' centres for the cube, on the axes.
+    pub fn mesh<S: Scalar>(&self) -> Mesh<Vec3<S>> {
+        let int = |v: [i64; 3]| Vec3::new(S::int(v[0]), S::int(v[1]), S::int(v[2]));
+        let (positions, faces): (Vec<Vec3<S>>, Vec<Vec<usize>>) = match self {
+            Self::Tetrahedron => (
+                [[1, 1, 1], [1, -1, -1], [-1, 1, -1], [-1, -1, 1]]
+                    .map(int)
+                    .to_vec(),
+                vec![vec![0, 1, 2], vec![1, 3, 2], vec![0, 2, 3], vec![0, 3, 1]],
+            ),
+            // Vertex i has its coordinates' signs in bits 0, 1 and 2 for x, y and z.
+            Self::Cube => (
+                (0..8)
+                    .map(|i| int([0, 1, 2].map(|b| if i >> b & 1 == 1 { 1 } else { -1 })))
+                    .collect(),
+                vec![
+                    vec![4, 5, 7, 6],
+                    vec![0, 2, 3, 1],
+                    vec![1, 3, 7, 5],
+                    vec![0, 4, 6, 2],
+                    vec![2, 6, 7, 3],
+                    vec![0, 1, 5, 4],
+                ],
+            ),
+            Self::Octahedron => (
+                [
+                    [1, 0, 0],
+                    [-1, 0, 0],
+                    [0, 1, 0],
+                    [0, -1, 0],
+                    [0, 0, 1],
+                    [0, 0, -1],
+                ]
+                .map(int)
+                .to_vec(),
+                vec![
+                    vec![0, 2, 4],
+                    vec![2, 1, 4],
+                    vec![1, 3, 4],
+                    vec![3, 0, 4],
+                    vec![2, 0, 5],
+                    vec![1, 2, 5],
+                    vec![3, 1, 5],
+                    vec![0, 3, 5],
+                ],
+            ),
+            Self::Dodecahedron | Self::Icosahedron => {
+                let s = if *self == Self::Dodecahedron {
+                    dodecahedron()
+                } else {
+                    icosahedron()
+                };
+                let positions = s
+                    .vertices()
+                    .iter()
+                    .map(|v| Vec3::from(v.to_scalar::<S>()))
+                    .collect();
+                (positions, s.faces())
+            }
+        };
+        let positions = positions.iter().map(|v| v.normalise()).collect();
+        Mesh::from_faces(positions, &faces).expect("consistent winding")
+    }
+
+    /// Inradius: radius of the inscribed sphere, touching the faces.
+    pub fn inr(&self) -> Val {
+        match self {
+            // sqrt(6) / 12
+            Self::Tetrahedron => sqrt(6).idiv(12),
+            Self::Cube => Val::from(1).idiv(2),
+            // sqrt(6) / 6
+            Self::Octahedron => sqrt(6).idiv(6),
+            // sqrt((25 + 11 sqrt(5)) / 10) / 2
+            Self::Dodecahedron => sqrt(5).imul(11).iadd(25).idiv(10).sqrt().idiv(2),
+            Self::Icosahedron => inr(),
+        }
+    }
+
+    /// Circumradius: radius of the sphere through the vertices.
+    pub fn cir(&self) -> Val {
+        match self {
+            // sqrt(6) / 4
+            Self::Tetrahedron => sqrt(6).idiv(4),
+            // sqrt(3) / 2
+            Self::Cube => sqrt(3).idiv(2),
+            // sqrt(2) / 2
+            Self::Octahedron => sqrt(2).idiv(2),
+            // sqrt(3) phi / 2
+            Self::Dodecahedron => sqrt(3).mul(&phi()).idiv(2),
+            Self::Icosahedron => cir(),
+        }
+    }
+
+    /// Midradius: radius of the sphere touching the edges at their midpoints.
+    pub fn mid(&self) -> Val {
+        match self {
+            // sqrt(2) / 4
+            Self::Tetrahedron => sqrt(2).idiv(4),
+            // sqrt(2) / 2
+            Self::Cube => sqrt(2).idiv(2),
+            Self::Octahedron => Val::from(1).idiv(2),
+            // phi^2 / 2
+            Self::Dodecahedron => phi().ipow(2).idiv(2),
+            Self::Icosahedron => mid(),
+        }
+    }
+
+    /// Surface area.
+    pub fn area(&self) -> Val {
+        match self {
+            Self::Tetrahedron => sqrt(3),
+            Self::Cube => 6.into(),
+            // 2 sqrt(3)
+            Self::Octahedron => sqrt(3).imul(2),
+            // 3 sqrt(25 + 10 sqrt(5))
+            Self::Dodecahedron => sqrt(5).imul(10).iadd(25).sqrt().imul(3),
+            // 5 sqrt(3)
+            Self::Icosahedron => sqrt(3).imul(5),
+        }
+    }
+
+    /// Volume; also a third of the area times the inradius.
+    pub fn volume(&self) -> Val {
+        match self {
+            // sqrt(2) / 12
+            Self::Tetrahedron => sqrt(2).idiv(12),
+            Self::Cube => 1.into(),
+            // sqrt(2) / 3
+            Self::Octahedron => sqrt(2).idiv(3),
+            // (15 + 7 sqrt(5)) / 4
+            Self::Dodecahedron => sqrt(5).imul(7).iadd(15).idiv(4),
+            // 5 (3 + sqrt(5)) / 12
+            Self::Icosahedron => sqrt(5).iadd(3).imul(5).idiv(12),
+        }
+    }
+
+    /// Angle between two faces meeting at an edge.
+    pub fn dihedral(&self) -> Angle {
+        match self {
+            // acos(1/3)
+            Self::Tetrahedron => Val::from(1).idiv(3).acos(),
+            Self::Cube => Angle::part(4),
+            // acos(-1/3)
+            Self::Octahedron => Val::from(-1).idiv(3).acos(),
+            // acos(-sqrt(5) / 5)
+            Self::Dodecahedron => sqrt(5).idiv(-5).acos(),
+            // acos(-sqrt(5) / 3)
+            Self::Icosahedron => sqrt(5).idiv(-3).acos(),
+        }
+    }
+
+    /// Interior angle of a face.
+    pub fn face_angle(&self) -> Angle {
+        let (p, _) = self.schlafli();
+        // (p - 2) pi / p
+        Angle::part(2 * p).imul(p - 2)
+    }
+
+    /// Solid angle at a vertex, in steradians.
+    /// The spherical excess of the q-gon cut out by the faces around the vertex.
+    pub fn solid_angle(&self) -> Angle {
+        let (_, q) = self.schlafli();
+        self.dihedral().imul(q).sub(&Angle::part(2).imul(q - 2))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::testing::outward;
+    use approx::assert_relative_eq;
+    use num_traits::ToPrimitive;
+    use std::f64::consts::{FRAC_1_SQRT_2, FRAC_PI_2};
+
+    #[test]
+    fn test_published() {
+        // inradius, circumradius, midradius, area, volume, dihedral (degrees), solid angle.
+        let table = [
+            (
+                Platonic::Tetrahedron,
+                [
+                    0.2041241452,
+                    0.6123724357,
+                    0.3535533906,
+                    1.7320508076,
+                    0.1178511302,
+                ],
+                70.5287793655,
+                0.5512855984,
+            ),
+            (
+                Platonic::Cube,
+                [0.5, 0.8660254038, FRAC_1_SQRT_2, 6.0, 1.0],
+                90.0,
+                FRAC_PI_2,
+            ),
+            (
+                Platonic::Octahedron,
+                [0.4082482905, FRAC_1_SQRT_2, 0.5, 3.4641016151, 0.4714045208],
+                109.4712206345,
+                1.3593476378,
+            ),
+            (
+                Platonic::Dodecahedron,
+                [
+                    1.1135163644,
+                    1.4012585384,
+                    1.3090169944,
+                    20.6457288071,
+                    7.6631189606,
+                ],
+                116.5650511771,
+                2.9617391538,
+            ),
+            (
+                Platonic::Icosahedron,
+                [
+                    0.7557613141,
+                    0.9510565163,
+                    0.8090169944,
+                    8.6602540378,
+                    2.1816949907,
+                ],
+                138.1896851042,
+                2.6345470260,
+            ),
+        ];
+        for (s, lengths, dihedral, solid) in table {
+            let got = [s.inr(), s.cir(), s.mid(), s.area(), s.volume()];
+            for (a, b) in got.iter().zip(lengths) {
+                assert_relative_eq!(a.to_f64().unwrap(), b, epsilon = 1e-9);
+            }
+            assert_relative_eq!(s.dihedral().to_degrees().unwrap(), dihedral, epsilon = 1e-9);
+            assert_relative_eq!(s.solid_angle().to_f64().unwrap(), solid, epsilon = 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_relations() {
+        for s in Platonic::all() {
+            let (p, _) = s.schlafli();
+            let (v, e, f) = s.counts();
+            assert_eq!(v - e + f, 2);
+            assert_eq!(s.dual().counts(), (f, e, v));
+            assert_eq!(s.dual().dual(), s);
+            assert_eq!(
+                s.face_angle().to_rational(),
+                Angle::part(2 * p).imul(p - 2).to_rational()
+            );
+
+            // Volume is a pyramid on each face; radii by Pythagoras, with the face's circumradius.
+            let volume = s.area().mul(&s.inr()).idiv(3);
+            assert_relative_eq!(
+                volume.to_f64().unwrap(),
+                s.volume().to_f64().unwrap(),
+                epsilon = 1e-12
+            );
+            let face_cir = Angle::part(2 * p).sin().rec().idiv(2);
+            let r2 = s.inr().ipow(2).add(&face_cir.ipow(2));
+            assert_relative_eq!(
+                r2.to_f64().unwrap(),
+                s.cir().ipow(2).to_f64().unwrap(),
+                epsilon = 1e-12
+            );
+            let r2 = s.mid().ipow(2).add(&Val::from(1).idiv(4));
+            assert_relative_eq!(
+                r2.to_f64().unwrap(),
+                s.cir().ipow(2).to_f64().unwrap(),
+                epsilon = 1e-12
+            );
+
+            // An edge subtends the supplement of the dual's dihedral angle at the centre.
+            let edge = s.mid().div(&s.cir()).acos().imul(2);
+            let sum = edge.add(&s.dual().dihedral());
+            assert_relative_eq!(
+                sum.to_f64().unwrap(),
+                Angle::part(2).to_f64().unwrap(),
+                epsilon = 1e-12
+            );
+        }
+    }
+
+    #[test]
+    fn test_meshes() {
+        for s in Platonic::all() {
+            let m: Mesh<Vec3<f64>> = s.mesh();
+            let (v, e, f) = s.counts();
+            assert_eq!(
+                (m.vertex_count(), m.edge_count(), m.face_count()),
+                (v as usize, e as usize, f as usize)
+            );
+            assert!(m.is_closed());
+            let (p, q) = s.schlafli();
+            for v in m.vertices() {
+                assert_relative_eq!(m.position(v).norm(), 1.0, epsilon = 1e-12);
+                assert_eq!(m.vertex_neighbours(v).len(), q as usize);
+            }
+
+            // Regular faces, winding outwards, with edges in the ratio to the circumradius.
+            let edge = s.cir().rec().to_f64().unwrap();
+            assert!(outward(&m));
+            for f in m.faces() {
+                let v = m.face_vertices(f);
+                assert_eq!(v.len(), p as usize);
+                for i in 0..v.len() {
+                    let d = m.position(v[(i + 1) % v.len()]).sub(m.position(v[i]));
+                    assert_relative_eq!(d.norm(), edge, epsilon = 1e-12);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,220 @@
+use std::cmp::Ordering;
+
+use crate::spherical::Norm;
+use crate::val::{Angle, Val};
+use crate::vec3::Vec3;
+
+/// Spherical polygon on the unit sphere, with vertices joined by great-circle arcs.
+/// Vertices go counterclockwise when looking at the polygon from outside the sphere.
+#[derive(Clone)]
+pub struct Polygon {
+    vertices: Vec<Norm>,
+}
+
+impl Polygon {
+    pub fn new(vertices: Vec<Norm>) -> Self {
+        Self { vertices }
+    }
+
+    pub fn triangle(a: &Norm, b: &Norm, c: &Norm) -> Self {
+        Self::new(vec![a.clone(), b.clone(), c.clone()])
+    }
+
+    pub fn vertices(&self) -> &[Norm] {
+        &self.vertices
+    }
+
+    /// Vertices as vectors.
+    fn vecs(&self) -> Vec<Vec3<Val>> {
+        self.vertices.iter().map(Vec3::from).collect()
+    }
+
+    /// Each vertex with the previous and next one.
+    fn corners(&self) -> impl Iterator<Item = [Vec3<Val>; 3]> {
+        let v = self.vecs();
+        let n = v.len();
+        (0..n).map(move |i| {
+            [
+                v[(i + n - 1) % n].clone(),
+                v[i].clone(),
+                v[(i + 1) % n].clone(),
+            ]
+        })
+    }
+
+    /// Interior angles, in the order of the vertices.
+    pub fn angles(&self) -> Vec<Angle> {
+        self.corners()
+            .map(|[a, b, c]| {
+                // Angle between the tangents towards c and a, counterclockwise about b.
+                let y = b.dot(&c.cross(&a)).simplify_trig();
+                let x = c.dot(&a).sub(&a.dot(&b).mul(&b.dot(&c))).simplify_trig();
+                let angle = y.atan2(&x);
+                match angle.cmp_certified(&Angle::zero()) {
+                    // Reflex angle.
+                    Some(Ordering::Less) => angle.add(&Angle::turn()),
+                    _ => angle,
+                }
+            })
+            .collect()
+    }
+
+    /// Spherical excess, i.e. the area on the unit sphere, in steradians.
+    /// Sums signed triangles fanning out from the first vertex (Van Oosterom & Strackee).
+    pub fn excess(&self) -> Angle {
+        let v = self.vecs();
+        v.windows(2)
+            .skip(1)
+            .map(|w| {
+                let (a, b, c) = (&v[0], &w[0], &w[1]);
+                let y = a.dot(&b.cross(c)).simplify_trig();
+                let x = Val::from(1)
+                    .add(&a.dot(b))
+                    .add(&b.dot(c))
+                    .add(&c.dot(a))
+                    .simplify_trig();
+                y.atan2(&x).imul(2)
+            })
+            .fold(Angle::zero(), |s, e| s.add(&e))
+    }
+
+    /// Area on a sphere of radius `r`.
+    /// Returned as an `Angle`, the excess scaled by r², so multiples of pi stay exact.
+    pub fn area(&self, r: &Val) -> Angle {
+        self.excess().mul(&r.ipow(2))
+    }
+
+    /// Sum of the great-circle arc lengths of the edges.
+    pub fn perimeter(&self) -> Angle {
+        let n = self.vertices.len();
+        (0..n)
+            .map(|i| self.vertices[i].angle_to(&self.vertices[(i + 1) % n]))
+            .fold(Angle::zero(), |s, e| s.add(&e))
+    }
+
+    /// Mean of the vertices, projected back onto the sphere.
+    /// Matches the area centroid for regular polygons.
+    pub fn centroid(&self) -> Norm {
+        self.vecs()
+            .iter()
+            .fold(Vec3::zero(), |s, v| s.add(v))
+            .simplify_trig()
+            .to_norm()
+    }
+
+    /// Checks whether a point is inside, by summing the angles subtended by the edges.
+    /// Returns `None` if it can't be certified, e.g. for points on the boundary, or with parameters.
+    pub fn contains(&self, p: &Norm) -> Option<bool> {
+        let p = Vec3::from(p);
+        let v = self.vecs();
+        let n = v.len();
+        let winding = (0..n)
+            .map(|i| {
+                let (a, b) = (&v[i], &v[(i + 1) % n]);
+                let y = p.dot(&a.cross(b));
+                let x = a.dot(b).sub(&p.dot(a).mul(&p.dot(b)));
+                // Possibly on the edge, where the sign of the half turn would be arbitrary.
+                let (yi, xi) = (y.to_interval()?, x.to_interval()?);
+                if yi.contains(0.0) && xi.hi() < 0.0 {
+                    return None;
+                }
+                Some(y.atan2(&x))
+            })
+            .try_fold(Angle::zero(), |s, e| e.map(|e| s.add(&e)))?;
+        // The winding is either 0 or a full turn.
+        match winding.cmp_certified(&Angle::turn().idiv(2))? {
+            Ordering::Less => Some(false),
+            Ordering::Greater => Some(true),
+            Ordering::Equal => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{alpha, beta};
+    use approx::assert_relative_eq;
+    use num_traits::ToPrimitive;
+
+    fn octant() -> Polygon {
+        let x = Norm::zero().south(&Angle::part(4));
+        Polygon::triangle(&Norm::zero(), &x, &x.east(&Angle::part(4)))
+    }
+
+    #[test]
+    fn test_octant() {
+        let p = octant();
+        for a in p.angles() {
+            assert_eq!(a.to_rational(), Angle::part(4).to_rational());
+        }
+        assert_eq!(p.excess().to_rational(), Angle::part(4).to_rational());
+        assert_eq!(p.area(&2.into()).to_rational(), Angle::turn().to_rational());
+        assert_relative_eq!(
+            p.perimeter().to_f64().unwrap(),
+            Angle::part(4).imul(3).to_f64().unwrap()
+        );
+
+        let c = p.centroid();
+        assert_relative_eq!(
+            c.x().to_f64().unwrap(),
+            1.0 / 3.0_f64.sqrt(),
+            epsilon = 1e-15
+        );
+        assert_eq!(p.contains(&c), Some(true));
+        assert_eq!(p.contains(&c.antipode()), Some(false));
+        assert_eq!(p.contains(&Norm::zero().south(&Angle::part(8))), None);
+    }
+
+    #[test]
+    fn test_icosahedron_face() {
+        let top = Norm::zero();
+        let v = |i: i64| top.south(&alpha()).east(&Angle::part(5).imul(i));
+        let face = Polygon::triangle(&top, &v(0), &v(1));
+        assert_relative_eq!(
+            face.excess().to_f64().unwrap(),
+            Angle::turn().imul(2).idiv(20).to_f64().unwrap(),
+            epsilon = 1e-12
+        );
+        for a in face.angles() {
+            assert_relative_eq!(
+                a.to_f64().unwrap(),
+                Angle::part(5).to_f64().unwrap(),
+                epsilon = 1e-12
+            );
+        }
+        assert_relative_eq!(
+            face.centroid().angle_to(&top).to_f64().unwrap(),
+            beta().to_f64().unwrap(),
+            epsilon = 1e-12
+        );
+    }
+
+    #[test]
+    fn test_concave() {
+        // A square around the north pole, with a notch reaching past the pole.
+        let ring = |i: i64| {
+            Norm::zero()
+                .south(&Angle::part(8))
+                .east(&Angle::part(4).imul(i))
+        };
+        let p = Polygon::new(vec![
+            ring(0),
+            ring(1),
+            ring(2),
+            Norm::zero().south(&Angle::part(32)).east(&Angle::part(8)),
+            ring(3),
+        ]);
+        let angles = p.angles();
+        assert!(angles[3].to_f64().unwrap() > Angle::part(2).to_f64().unwrap());
+        let sum = angles.iter().fold(0.0, |s, a| s + a.to_f64().unwrap());
+        assert_relative_eq!(
+            sum - 3.0 * std::f64::consts::PI,
+            p.excess().to_f64().unwrap(),
+            epsilon = 1e-12
+        );
+        assert_eq!(p.contains(&Norm::zero()), Some(false));
+        let q = Norm::zero().south(&Angle::part(12)).east(&Angle::part(8));
+        assert_eq!(p.contains(&q), Some(true));
+    }
+}
@@ -0,0 +1,198 @@
+use crate::scalar::{Scalar, ScalarAngle};
+use crate::val::Angle;
+use crate::vec3::Vec3;
+
+/// Ways of placing the points of a subdivided icosahedron face on the sphere.
+///
+/// Points are given by barycentric coordinates in a face; all methods keep the corners in place,
+/// and agree on which points end up on the edges.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Projection {
+    /// Method 1: subdivide the flat face, then project from the centre.
+    Flat,
+    /// Method 2: equal arcs along the edges, by weighting the corners with the sines of the partial arcs.
+    EqualArc,
+    /// Method 3: lines of the flat lattice become great circles through equal-arc points on the edges;
+    /// the three that should meet at a point form a small triangle instead, and its centre is used.
+    GreatCircle,
+    /// Snyder's equal-area projection: equal areas of the flat face map to equal areas of the sphere.
+    Snyder,
+}
+
+impl Projection {
+    pub fn all() -> [Self; 4] {
+        [Self::Flat, Self::EqualArc, Self::GreatCircle, Self::Snyder]
+    }
+
+    /// The point at barycentric coordinates `l / t` in the spherical triangle `face` (unit vectors).
+    /// The coordinates are non-negative and add up to `t`.
+    pub fn point<S: Scalar>(&self, face: &[Vec3<S>; 3], l: [i64; 3], t: i64) -> Vec3<S> {
+        if let Some(k) = (0..3).find(|&k| l[k] == t) {
+            return face[k].clone();
+        }
+        let frac = |a: i64| S::int(a).div(&S::int(t));
+        match self {
+            Self::Flat => (0..3)
+                .fold(Vec3::zero(), |sum, k| {
+                    sum.add(&face[k].scale(&S::int(l[k])))
+                })
+                .normalise(),
+            Self::EqualArc => {
+                let arc = face[0].dot(&face[1]).acos();
+                (0..3)
+                    .fold(Vec3::zero(), |sum, k| {
+                        sum.add(&face[k].scale(&arc.mul(&frac(l[k])).sin()))
+                    })
+                    .normalise()
+            }
+            Self::GreatCircle => {
+                // Great circles of constant l[k], through the points at the same arc from the other corners.
+                // Their normals point towards corner k, so consecutive ones cross on this side of the sphere.
+                let circles: Vec<Vec3<S>> = (0..3)
+                    .map(|k| {
+                        let rest = frac(t - l[k]);
+                        let a = slerp(&face[k], &face[(k + 1) % 3], &rest);
+                        let b = slerp(&face[k], &face[(k + 2) % 3], &rest);
+                        a.cross(&b)
+                    })
+                    .collect();
+                (0..3)
+                    .fold(Vec3::zero(), |sum, k| {
+                        sum.add(&circles[k].cross(&circles[(k + 1) % 3]).normalise())
+                    })
+                    .normalise()
+            }
+            Self::Snyder => snyder(face, l, t),
+        }
+    }
+}
+
+/// Point at fraction `t` along the arc from `a` to `b`.
+fn slerp<S: Scalar>(a: &Vec3<S>, b: &Vec3<S>, t: &S) -> Vec3<S> {
+    let arc = a.dot(b).acos();
+    let (u, v) = (arc.mul(&S::int(1).sub(t)).sin(), arc.mul(t).sin());
+    a.scale(&u).add(&b.scale(&v)).normalise()
+}
+
+/// Inverse of Snyder's projection, from the flat face onto the sphere.
+///
+/// The face is split into six right triangles by its medians.
+/// In the one with corner V, next to the edge towards W, the ray from the centre P through the point
+/// meets the edge at E; the area of PVE fixes where that ray meets the edge on the sphere, at Q.
+/// Along the ray, the sine of half the arc from P grows linearly, as in Lambert's azimuthal projection.
+fn snyder<S: Scalar>(face: &[Vec3<S>; 3], l: [i64; 3], t: i64) -> Vec3<S> {
+    let centre = face[0].add(&face[1]).add(&face[2]).normalise();
+    // Corners by barycentric coordinate, decreasing: V, W, and the one opposite the edge.
+    let mut order = [0, 1, 2];
+    order.sort_by_key(|&k| -l[k]);
+    let [v, w, u] = order;
+    let rest = t - 3 * l[u];
+    if rest == 0 {
+        return centre;
+    }
+
+    // Area of PVE, as a fraction of PVM with M the midpoint of the edge; PVM is a 120th of the sphere.
+    let area = <S::Angle as ScalarAngle<S>>::turn()
+        .idiv(60)
+        .mul(&S::int(2 * (l[w] - l[u])).div(&S::int(rest)));
+
+    // Q on the arc from V towards W, so that PVQ has the given area; Arvo's formula, with the angle at V fixed.
+    let alpha = S::Angle::from_angle(&Angle::part(10));
+    let (sin_a, cos_a) = (alpha.sin(), alpha.cos());
+    let d = area.sub(&alpha);
+    let (s, c) = (d.sin(), d.cos());
+    let p = c.sub(&cos_a);
+    let q = s.add(&sin_a.mul(&face[v].dot(&centre)));
+    let num = q.mul(&c).sub(&p.mul(&s)).mul(&cos_a).sub(&q);
+    let cos = num.div(&q.mul(&s).add(&p.mul(&c)).mul(&sin_a));
+    let sin = S::int(1).sub(&cos.mul(&cos)).sqrt();
+    let q = face[v]
+        .scale(&cos)
+        .add(&tangent(&face[v], &face[w]).scale(&sin));
+
+    // The point along the arc from P to Q.
+    let half = S::int(1).sub(&centre.dot(&q)).div(&S::int(2)).sqrt();
+    let h = half.mul(&S::int(rest)).div(&S::int(t));
+    let cos = S::int(1).sub(&S::int(2).mul(&h.mul(&h)));
+    let sin = S::int(2).mul(&h).mul(&S::int(1).sub(&h.mul(&h)).sqrt());
+    centre.scale(&cos).add(&tangent(&centre, &q).scale(&sin))
+}
+
+/// Unit vector at `a`, along the arc towards `b`.
+fn tangent<S: Scalar>(a: &Vec3<S>, b: &Vec3<S>) -> Vec3<S> {
+    b.sub(&a.scale(&a.dot(b))).normalise()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::geodesic::Geodesic;
+    use crate::icos::icosahedron;
+    use approx::assert_relative_eq;
+    use std::f64::consts::PI;
+
+    fn face() -> [Vec3<f64>; 3] {
+        let s = icosahedron();
+        [0, 1, 2].map(|k| Vec3::from(s.vertices()[s.faces()[0][k]].to_scalar::<f64>()))
+    }
+
+    fn angle(a: &Vec3<f64>, b: &Vec3<f64>) -> f64 {
+        a.cross(b).norm().atan2(a.dot(b))
+    }
+
+    #[test]
+    fn test_edges() {
+        let f = face();
+        let edge = angle(&f[0], &f[1]);
+        for p in Projection::all() {
+            for k in 0..3 {
+                assert_eq!(
+                    p.point(&f, [0, 1, 2].map(|i| if i == k { 4 } else { 0 }), 4),
+                    f[k]
+                );
+            }
+            for i in 0..=4 {
+                let x = p.point(&f, [4 - i, i, 0], 4);
+                assert_relative_eq!(x.norm(), 1.0, epsilon = 1e-12);
+                // On the edge, and at the midpoint in the middle.
+                assert_relative_eq!(f[0].cross(&f[1]).dot(&x), 0.0, epsilon = 1e-12);
+                if i == 2 {
+                    assert_relative_eq!(angle(&f[0], &x), edge / 2.0, epsilon = 1e-12);
+                }
+                // Equal arcs, where the methods say so.
+                if matches!(p, Projection::EqualArc | Projection::GreatCircle) {
+                    assert_relative_eq!(angle(&f[0], &x), edge * i as f64 / 4.0, epsilon = 1e-12);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_snyder() {
+        // Along the edge, the triangle cut off with the face centre grows linearly in area, up to a 120th of the sphere.
+        let f = face();
+        let centre = f[0].add(&f[1]).add(&f[2]).normalise();
+        for i in 0..=6 {
+            let x = Projection::Snyder.point(&f, [12 - i, i, 0], 12);
+            let num = centre.dot(&f[0].cross(&x));
+            let den = 1.0 + centre.dot(&f[0]) + f[0].dot(&x) + x.dot(&centre);
+            let area = 2.0 * num.atan2(den);
+            assert_relative_eq!(area, PI / 30.0 * i as f64 / 6.0, epsilon = 1e-12);
+        }
+
+        // Same topology with all methods; subdivisions are more even than with flat ones.
+        let flat = Geodesic::<f64>::new(4, 4).unwrap();
+        for p in Projection::all() {
+            let g = Geodesic::<f64>::with_projection(4, 4, p).unwrap();
+            for f in g.mesh().faces() {
+                assert_eq!(g.mesh().face_vertices(f), flat.mesh().face_vertices(f));
+            }
+            let total: f64 = g.areas().iter().sum();
+            assert_relative_eq!(total, 4.0 * PI, epsilon = 1e-9);
+            if p != Projection::Flat {
+                assert!(g.area_spread().ratio() < flat.area_spread().ratio());
+                assert!(g.edge_spread().ratio() < flat.edge_spread().ratio());
+            }
+        }
+    }
+}
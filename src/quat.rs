@@ -0,0 +1,236 @@
+use crate::scalar::{Scalar, ScalarAngle};
+use crate::spherical::Norm;
+use crate::vec3::Vec3;
+
+/// Quaternion w + xi + yj + zk.
+/// Unit quaternions represent rotations, counterclockwise about their axis when looking towards the origin.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Quat<T> {
+    pub w: T,
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+impl<T> Quat<T> {
+    pub fn new(w: T, x: T, y: T, z: T) -> Self {
+        Self { w, x, y, z }
+    }
+}
+
+impl<S: Scalar> Quat<S> {
+    /// The identity rotation.
+    pub fn one() -> Self {
+        Self::new(S::int(1), S::int(0), S::int(0), S::int(0))
+    }
+
+    /// Pure quaternion with the vector as its imaginary part.
+    pub fn pure(v: &Vec3<S>) -> Self {
+        Self::new(S::int(0), v.x.clone(), v.y.clone(), v.z.clone())
+    }
+
+    /// Rotation about `axis` by `by`.
+    pub fn from_axis_angle(axis: &Norm<S>, by: &S::Angle) -> Self {
+        let half = by.idiv(2);
+        let sin = half.sin();
+        Self::new(
+            half.cos(),
+            axis.x().mul(&sin),
+            axis.y().mul(&sin),
+            axis.z().mul(&sin),
+        )
+    }
+
+    /// Rotation about the x axis.
+    pub fn rot_x(by: &S::Angle) -> Self {
+        let half = by.idiv(2);
+        Self::new(half.cos(), half.sin(), S::int(0), S::int(0))
+    }
+
+    /// Rotation about the y axis.
+    pub fn rot_y(by: &S::Angle) -> Self {
+        let half = by.idiv(2);
+        Self::new(half.cos(), S::int(0), half.sin(), S::int(0))
+    }
+
+    /// Rotation about the z axis.
+    pub fn rot_z(by: &S::Angle) -> Self {
+        let half = by.idiv(2);
+        Self::new(half.cos(), S::int(0), S::int(0), half.sin())
+    }
+
+    /// Imaginary part, as a vector.
+    pub fn vector(&self) -> Vec3<S> {
+        Vec3::new(self.x.clone(), self.y.clone(), self.z.clone())
+    }
+
+    /// Hamilton product, `self * a`.
+    pub fn mul(&self, a: &Self) -> Self {
+        Self::new(
+            self.w
+                .mul(&a.w)
+                .sub(&self.x.mul(&a.x))
+                .sub(&self.y.mul(&a.y))
+                .sub(&self.z.mul(&a.z)),
+            self.w
+                .mul(&a.x)
+                .add(&self.x.mul(&a.w))
+                .add(&self.y.mul(&a.z))
+                .sub(&self.z.mul(&a.y)),
+            self.w
+                .mul(&a.y)
+                .sub(&self.x.mul(&a.z))
+                .add(&self.y.mul(&a.w))
+                .add(&self.z.mul(&a.x)),
+            self.w
+                .mul(&a.z)
+                .add(&self.x.mul(&a.y))
+                .sub(&self.y.mul(&a.x))
+                .add(&self.z.mul(&a.w)),
+        )
+    }
+
+    /// Conjugate; the inverse rotation for unit quaternions.
+    pub fn conj(&self) -> Self {
+        Self::new(self.w.clone(), self.x.neg(), self.y.neg(), self.z.neg())
+    }
+
+    pub fn norm_sq(&self) -> S {
+        self.w
+            .ipow(2)
+            .add(&self.x.ipow(2))
+            .add(&self.y.ipow(2))
+            .add(&self.z.ipow(2))
+    }
+
+    pub fn norm(&self) -> S {
+        self.norm_sq().sqrt()
+    }
+
+    /// Unit quaternion, representing the same rotation.
+    pub fn normalise(&self) -> Self {
+        let r = S::int(1).div(&self.norm());
+        Self::new(
+            self.w.mul(&r),
+            self.x.mul(&r),
+            self.y.mul(&r),
+            self.z.mul(&r),
+        )
+    }
+
+    /// The rotation applying `self` first, then `then`.
+    pub fn compose(&self, then: &Self) -> Self {
+        then.mul(self)
+    }
+
+    /// Axis and angle of a unit quaternion; the angle is in [0, 2pi].
+    /// The axis is arbitrary for the identity rotation.
+    pub fn to_axis_angle(&self) -> (Norm<S>, S::Angle) {
+        let v = self.vector();
+        (v.to_norm(), v.norm().atan2(&self.w).imul(2))
+    }
+
+    /// Rotation matrix of a unit quaternion, in row-major order.
+    pub fn to_matrix(&self) -> [[S; 3]; 3] {
+        let (w, x, y, z) = (&self.w, &self.x, &self.y, &self.z);
+        let two = S::int(2);
+        let one = |a: &S, b: &S| S::int(1).sub(&a.ipow(2).add(&b.ipow(2)).mul(&two));
+        let sum = |a: S, b: S| a.add(&b).mul(&two);
+        let dif = |a: S, b: S| a.sub(&b).mul(&two);
+        [
+            [one(y, z), dif(x.mul(y), w.mul(z)), sum(x.mul(z), w.mul(y))],
+            [sum(x.mul(y), w.mul(z)), one(x, z), dif(y.mul(z), w.mul(x))],
+            [dif(x.mul(z), w.mul(y)), sum(y.mul(z), w.mul(x)), one(x, y)],
+        ]
+    }
+
+    /// Rotates a vector by a unit quaternion.
+    pub fn apply_vec(&self, v: &Vec3<S>) -> Vec3<S> {
+        self.mul(&Self::pure(v)).mul(&self.conj()).vector()
+    }
+
+    /// Rotates a point by a unit quaternion.
+    pub fn apply(&self, n: &Norm<S>) -> Norm<S> {
+        self.apply_vec(&n.into()).to_norm()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::val::{Angle, Val};
+    use approx::assert_relative_eq;
+    use num_traits::ToPrimitive;
+
+    fn assert_vec_eq(a: &Vec3<Val>, b: &Vec3<Val>) {
+        for (x, y) in a.clone().to_array().iter().zip(b.clone().to_array().iter()) {
+            assert_relative_eq!(x.to_f64().unwrap(), y.to_f64().unwrap(), epsilon = 1e-12);
+        }
+    }
+
+    fn v(x: i64, y: i64, z: i64) -> Vec3<Val> {
+        Vec3::new(x.into(), y.into(), z.into())
+    }
+
+    #[test]
+    fn test_axes() {
+        let q = Angle::part(4);
+        assert_vec_eq(&Quat::rot_z(&q).apply_vec(&v(1, 0, 0)), &v(0, 1, 0));
+        assert_vec_eq(&Quat::rot_x(&q).apply_vec(&v(0, 1, 0)), &v(0, 0, 1));
+        assert_vec_eq(&Quat::rot_y(&q).apply_vec(&v(0, 0, 1)), &v(1, 0, 0));
+
+        // The same rotations, about axes given as points.
+        let x = Norm::zero().south(&q);
+        let y = x.east(&q);
+        assert_vec_eq(
+            &Quat::from_axis_angle(&Norm::zero(), &q).apply_vec(&v(1, 0, 0)),
+            &v(0, 1, 0),
+        );
+        assert_vec_eq(
+            &Quat::from_axis_angle(&x, &q).apply_vec(&v(0, 1, 0)),
+            &v(0, 0, 1),
+        );
+        assert_vec_eq(
+            &Quat::from_axis_angle(&y, &q).apply_vec(&v(0, 0, 1)),
+            &v(1, 0, 0),
+        );
+    }
+
+    #[test]
+    fn test_compose() {
+        let a = Quat::rot_x(&Angle::part(4));
+        let b = Quat::rot_y(&Angle::part(4));
+        let ab = a.compose(&b);
+        let p = v(1, 2, 3);
+        assert_vec_eq(&ab.apply_vec(&p), &b.apply_vec(&a.apply_vec(&p)));
+        assert_vec_eq(&ab.compose(&ab.conj()).apply_vec(&p), &p);
+
+        // x then y by a quarter turn is a third of a turn about (1, 1, -1).
+        let (axis, by) = ab.to_axis_angle();
+        assert_relative_eq!(by.to_f64().unwrap(), Angle::part(3).to_f64().unwrap());
+        assert_vec_eq(&axis.into(), &v(1, 1, -1).normalise());
+    }
+
+    #[test]
+    fn test_matrix() {
+        let q: Quat<Val> = Quat::new(1.into(), 2.into(), 3.into(), 4.into()).normalise();
+        assert_relative_eq!(q.norm().to_f64().unwrap(), 1.0, epsilon = 1e-15);
+        let m = q.to_matrix();
+        let p = v(3, -1, 2);
+        let mp = Vec3::new(
+            m[0][0]
+                .mul(&p.x)
+                .add(&m[0][1].mul(&p.y))
+                .add(&m[0][2].mul(&p.z)),
+            m[1][0]
+                .mul(&p.x)
+                .add(&m[1][1].mul(&p.y))
+                .add(&m[1][2].mul(&p.z)),
+            m[2][0]
+                .mul(&p.x)
+                .add(&m[2][1].mul(&p.y))
+                .add(&m[2][2].mul(&p.z)),
+        );
+        assert_vec_eq(&mp, &q.apply_vec(&p));
+    }
+}
@@ -0,0 +1,623 @@
+use std::collections::HashMap;
+
+use crate::val::{Angle, Cond, Val};
+
+/// A pattern matching values.
+#[derive(Clone, Debug)]
+pub enum Pat {
+    /// Wildcard, binding any value to a slot.
+    /// Repeated slots must bind structurally equal values.
+    Var(usize),
+    /// Matches a structurally equal value.
+    Lit(Val),
+    // Numeric ops:
+    Sum(Box<Self>, Box<Self>),
+    Dif(Box<Self>, Box<Self>),
+    Prd(Box<Self>, Box<Self>),
+    Rat(Box<Self>, Box<Self>),
+    Pow(Box<Self>, Box<Self>),
+    Sqrt(Box<Self>),
+    // Trig fns:
+    Sin(Box<AnglePat>),
+    Cos(Box<AnglePat>),
+    Tan(Box<AnglePat>),
+    // Other fns:
+    Exp(Box<Self>),
+    Ln(Box<Self>),
+    Abs(Box<Self>),
+    Sign(Box<Self>),
+    Min(Box<Self>, Box<Self>),
+    Max(Box<Self>, Box<Self>),
+}
+
+/// A pattern matching angles.
+#[derive(Clone, Debug)]
+pub enum AnglePat {
+    /// Wildcard, binding any angle to a slot.
+    Var(usize),
+    /// Matches a structurally equal angle.
+    Lit(Angle),
+    Pi(Box<Pat>),
+    // Numeric ops:
+    Sum(Box<Self>, Box<Self>),
+    Dif(Box<Self>, Box<Self>),
+    Prd(Box<Self>, Box<Pat>),
+    Rat(Box<Self>, Box<Pat>),
+    // Trig fns:
+    ASin(Box<Pat>),
+    ACos(Box<Pat>),
+    ATan(Box<Pat>),
+    ATan2(Box<Pat>, Box<Pat>),
+}
+
+/// Values and angles bound to wildcard slots during a match.
+#[derive(Clone, Debug, Default)]
+pub struct Bindings {
+    vals: HashMap<usize, Val>,
+    angles: HashMap<usize, Angle>,
+}
+
+/// Extra condition checked after a pattern matched.
+pub type Guard = Box<dyn Fn(&Bindings) -> bool>;
+
+/// A rewrite rule, replacing matches of the left side with the right side.
+pub struct Rule {
+    sides: Sides,
+    guard: Option<Guard>,
+}
+
+enum Sides {
+    Val(Pat, Pat),
+    Angle(AnglePat, AnglePat),
+}
+
+/// Where to look for the next redex.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Strategy {
+    /// Rewrite the leftmost subterm that has no rewritable subterms, i.e. bottom-up.
+    Innermost,
+    /// Rewrite the leftmost subterm closest to the root, i.e. top-down.
+    Outermost,
+}
+
+/// Term-rewriting engine over values and angles.
+pub struct Rewriter {
+    rules: Vec<Rule>,
+    strategy: Strategy,
+    max_steps: usize,
+}
+
+impl Pat {
+    pub fn var(i: usize) -> Self {
+        Self::Var(i)
+    }
+
+    pub fn add(&self, a: &Self) -> Self {
+        Self::Sum(Box::new(self.clone()), Box::new(a.clone()))
+    }
+
+    pub fn sub(&self, a: &Self) -> Self {
+        Self::Dif(Box::new(self.clone()), Box::new(a.clone()))
+    }
+
+    pub fn mul(&self, a: &Self) -> Self {
+        Self::Prd(Box::new(self.clone()), Box::new(a.clone()))
+    }
+
+    pub fn div(&self, a: &Self) -> Self {
+        Self::Rat(Box::new(self.clone()), Box::new(a.clone()))
+    }
+
+    pub fn pow(&self, a: &Self) -> Self {
+        Self::Pow(Box::new(self.clone()), Box::new(a.clone()))
+    }
+
+    pub fn sqrt(&self) -> Self {
+        Self::Sqrt(Box::new(self.clone()))
+    }
+
+    pub fn pi(&self) -> AnglePat {
+        AnglePat::Pi(Box::new(self.clone()))
+    }
+
+    pub fn asin(&self) -> AnglePat {
+        AnglePat::ASin(Box::new(self.clone()))
+    }
+
+    pub fn acos(&self) -> AnglePat {
+        AnglePat::ACos(Box::new(self.clone()))
+    }
+
+    pub fn atan(&self) -> AnglePat {
+        AnglePat::ATan(Box::new(self.clone()))
+    }
+
+    /// Matches the pattern against a value, extending the bindings.
+    pub fn matches(&self, v: &Val, b: &mut Bindings) -> bool {
+        match (self, v) {
+            (Self::Var(i), _) => match b.vals.get(i) {
+                Some(w) => w == v,
+                None => {
+                    b.vals.insert(*i, v.clone());
+                    true
+                }
+            },
+            (Self::Lit(l), _) => l == v,
+            (Self::Sum(p, q), Val::Sum(x, y))
+            | (Self::Dif(p, q), Val::Dif(x, y))
+            | (Self::Prd(p, q), Val::Prd(x, y))
+            | (Self::Rat(p, q), Val::Rat(x, y))
+            | (Self::Pow(p, q), Val::Pow(x, y))
+            | (Self::Min(p, q), Val::Min(x, y))
+            | (Self::Max(p, q), Val::Max(x, y)) => p.matches(x, b) && q.matches(y, b),
+            (Self::Sqrt(p), Val::Sqrt(x))
+            | (Self::Exp(p), Val::Exp(x))
+            | (Self::Ln(p), Val::Ln(x))
+            | (Self::Abs(p), Val::Abs(x))
+            | (Self::Sign(p), Val::Sign(x)) => p.matches(x, b),
+            (Self::Sin(p), Val::Sin(x))
+            | (Self::Cos(p), Val::Cos(x))
+            | (Self::Tan(p), Val::Tan(x)) => p.matches(x, b),
+            _ => false,
+        }
+    }
+
+    /// Builds a value by replacing wildcards with their bindings.
+    /// Panics if a wildcard is unbound.
+    pub fn build(&self, b: &Bindings) -> Val {
+        let bx = |p: &Self| Box::new(p.build(b));
+        match self {
+            Self::Var(i) => b.val(*i).clone(),
+            Self::Lit(l) => l.clone(),
+            Self::Sum(p, q) => Val::Sum(bx(p), bx(q)),
+            Self::Dif(p, q) => Val::Dif(bx(p), bx(q)),
+            Self::Prd(p, q) => Val::Prd(bx(p), bx(q)),
+            Self::Rat(p, q) => Val::Rat(bx(p), bx(q)),
+            Self::Pow(p, q) => Val::Pow(bx(p), bx(q)),
+            Self::Sqrt(p) => Val::Sqrt(bx(p)),
+            Self::Sin(p) => Val::Sin(p.build(b)),
+            Self::Cos(p) => Val::Cos(p.build(b)),
+            Self::Tan(p) => Val::Tan(p.build(b)),
+            Self::Exp(p) => Val::Exp(bx(p)),
+            Self::Ln(p) => Val::Ln(bx(p)),
+            Self::Abs(p) => Val::Abs(bx(p)),
+            Self::Sign(p) => Val::Sign(bx(p)),
+            Self::Min(p, q) => Val::Min(bx(p), bx(q)),
+            Self::Max(p, q) => Val::Max(bx(p), bx(q)),
+        }
+    }
+}
+
+impl AnglePat {
+    pub fn var(i: usize) -> Self {
+        Self::Var(i)
+    }
+
+    pub fn add(&self, a: &Self) -> Self {
+        Self::Sum(Box::new(self.clone()), Box::new(a.clone()))
+    }
+
+    pub fn sub(&self, a: &Self) -> Self {
+        Self::Dif(Box::new(self.clone()), Box::new(a.clone()))
+    }
+
+    pub fn mul(&self, a: &Pat) -> Self {
+        Self::Prd(Box::new(self.clone()), Box::new(a.clone()))
+    }
+
+    pub fn div(&self, a: &Pat) -> Self {
+        Self::Rat(Box::new(self.clone()), Box::new(a.clone()))
+    }
+
+    pub fn sin(&self) -> Pat {
+        Pat::Sin(Box::new(self.clone()))
+    }
+
+    pub fn cos(&self) -> Pat {
+        Pat::Cos(Box::new(self.clone()))
+    }
+
+    pub fn tan(&self) -> Pat {
+        Pat::Tan(Box::new(self.clone()))
+    }
+
+    /// Matches the pattern against an angle, extending the bindings.
+    pub fn matches(&self, a: &Angle, b: &mut Bindings) -> bool {
+        match (self, a) {
+            (Self::Var(i), _) => match b.angles.get(i) {
+                Some(w) => w == a,
+                None => {
+                    b.angles.insert(*i, a.clone());
+                    true
+                }
+            },
+            (Self::Lit(l), _) => l == a,
+            (Self::Pi(p), Angle::Pi(x))
+            | (Self::ASin(p), Angle::ASin(x))
+            | (Self::ACos(p), Angle::ACos(x))
+            | (Self::ATan(p), Angle::ATan(x)) => p.matches(x, b),
+            (Self::Sum(p, q), Angle::Sum(x, y)) | (Self::Dif(p, q), Angle::Dif(x, y)) => {
+                p.matches(x, b) && q.matches(y, b)
+            }
+            (Self::Prd(p, q), Angle::Prd(x, y)) | (Self::Rat(p, q), Angle::Rat(x, y)) => {
+                p.matches(x, b) && q.matches(y, b)
+            }
+            (Self::ATan2(p, q), Angle::ATan2(x, y)) => p.matches(x, b) && q.matches(y, b),
+            _ => false,
+        }
+    }
+
+    /// Builds an angle by replacing wildcards with their bindings.
+    /// Panics if a wildcard is unbound.
+    pub fn build(&self, b: &Bindings) -> Angle {
+        let bx = |p: &Self| Box::new(p.build(b));
+        let bv = |p: &Pat| Box::new(p.build(b));
+        match self {
+            Self::Var(i) => b.angle(*i).clone(),
+            Self::Lit(l) => l.clone(),
+            Self::Pi(p) => Angle::Pi(bv(p)),
+            Self::Sum(p, q) => Angle::Sum(bx(p), bx(q)),
+            Self::Dif(p, q) => Angle::Dif(bx(p), bx(q)),
+            Self::Prd(p, q) => Angle::Prd(bx(p), bv(q)),
+            Self::Rat(p, q) => Angle::Rat(bx(p), bv(q)),
+            Self::ASin(p) => Angle::ASin(bv(p)),
+            Self::ACos(p) => Angle::ACos(bv(p)),
+            Self::ATan(p) => Angle::ATan(bv(p)),
+            Self::ATan2(p, q) => Angle::ATan2(bv(p), bv(q)),
+        }
+    }
+}
+
+impl From<Val> for Pat {
+    fn from(item: Val) -> Self {
+        Pat::Lit(item)
+    }
+}
+
+impl From<i64> for Pat {
+    fn from(item: i64) -> Self {
+        Pat::Lit(item.into())
+    }
+}
+
+impl From<Angle> for AnglePat {
+    fn from(item: Angle) -> Self {
+        AnglePat::Lit(item)
+    }
+}
+
+impl Bindings {
+    /// The value bound to a slot.
+    pub fn val(&self, i: usize) -> &Val {
+        self.vals
+            .get(&i)
+            .unwrap_or_else(|| panic!("unbound value slot {}", i))
+    }
+
+    /// The angle bound to a slot.
+    pub fn angle(&self, i: usize) -> &Angle {
+        self.angles
+            .get(&i)
+            .unwrap_or_else(|| panic!("unbound angle slot {}", i))
+    }
+}
+
+impl Rule {
+    /// Rule rewriting values.
+    pub fn val(lhs: Pat, rhs: Pat) -> Self {
+        Self {
+            sides: Sides::Val(lhs, rhs),
+            guard: None,
+        }
+    }
+
+    /// Rule rewriting angles.
+    pub fn angle(lhs: AnglePat, rhs: AnglePat) -> Self {
+        Self {
+            sides: Sides::Angle(lhs, rhs),
+            guard: None,
+        }
+    }
+
+    /// Only applies the rule if the guard accepts the bindings.
+    pub fn when(self, guard: impl Fn(&Bindings) -> bool + 'static) -> Self {
+        Self {
+            guard: Some(Box::new(guard)),
+            ..self
+        }
+    }
+
+    fn accepts(&self, b: &Bindings) -> bool {
+        self.guard.as_ref().is_none_or(|g| g(b))
+    }
+
+    fn apply_val(&self, v: &Val) -> Option<Val> {
+        match &self.sides {
+            Sides::Val(lhs, rhs) => {
+                let mut b = Bindings::default();
+                (lhs.matches(v, &mut b) && self.accepts(&b)).then(|| rhs.build(&b))
+            }
+            Sides::Angle(_, _) => None,
+        }
+    }
+
+    fn apply_angle(&self, a: &Angle) -> Option<Angle> {
+        match &self.sides {
+            Sides::Angle(lhs, rhs) => {
+                let mut b = Bindings::default();
+                (lhs.matches(a, &mut b) && self.accepts(&b)).then(|| rhs.build(&b))
+            }
+            Sides::Val(_, _) => None,
+        }
+    }
+}
+
+impl Rewriter {
+    /// Empty rewriter, using the innermost strategy and at most 1000 steps.
+    pub fn new() -> Self {
+        Self {
+            rules: vec![],
+            strategy: Strategy::Innermost,
+            max_steps: 1000,
+        }
+    }
+
+    /// Registers a rule. Rules are tried in registration order.
+    pub fn rule(mut self, r: Rule) -> Self {
+        self.rules.push(r);
+        self
+    }
+
+    pub fn strategy(self, strategy: Strategy) -> Self {
+        Self { strategy, ..self }
+    }
+
+    /// Limits the number of rewrite steps, for rule sets that don't terminate on their own.
+    pub fn max_steps(self, max_steps: usize) -> Self {
+        Self { max_steps, ..self }
+    }
+
+    /// Rewrites a value until no rule applies or the step limit is reached.
+    pub fn rewrite(&self, v: &Val) -> Val {
+        let mut v = v.clone();
+        for _ in 0..self.max_steps {
+            match self.step_val(&v) {
+                Some(w) => v = w,
+                None => break,
+            }
+        }
+        v
+    }
+
+    /// Rewrites an angle until no rule applies or the step limit is reached.
+    pub fn rewrite_angle(&self, a: &Angle) -> Angle {
+        let mut a = a.clone();
+        for _ in 0..self.max_steps {
+            match self.step_angle(&a) {
+                Some(b) => a = b,
+                None => break,
+            }
+        }
+        a
+    }
+
+    fn root_val(&self, v: &Val) -> Option<Val> {
+        self.rules.iter().find_map(|r| r.apply_val(v))
+    }
+
+    fn root_angle(&self, a: &Angle) -> Option<Angle> {
+        self.rules.iter().find_map(|r| r.apply_angle(a))
+    }
+
+    /// Performs a single rewrite step, if any rule applies.
+    fn step_val(&self, v: &Val) -> Option<Val> {
+        if self.strategy == Strategy::Outermost {
+            if let Some(w) = self.root_val(v) {
+                return Some(w);
+            }
+        }
+        let sub = match v {
+            Val::Sum(x, y) => self.step_pair(x, y).map(|(x, y)| Val::Sum(x, y)),
+            Val::Dif(x, y) => self.step_pair(x, y).map(|(x, y)| Val::Dif(x, y)),
+            Val::Prd(x, y) => self.step_pair(x, y).map(|(x, y)| Val::Prd(x, y)),
+            Val::Rat(x, y) => self.step_pair(x, y).map(|(x, y)| Val::Rat(x, y)),
+            Val::Pow(x, y) => self.step_pair(x, y).map(|(x, y)| Val::Pow(x, y)),
+            Val::Sqrt(x) => self.step_val(x).map(|x| Val::Sqrt(Box::new(x))),
+            Val::Sin(a) => self.step_angle(a).map(Val::Sin),
+            Val::Cos(a) => self.step_angle(a).map(Val::Cos),
+            Val::Tan(a) => self.step_angle(a).map(Val::Tan),
+            Val::Exp(x) => self.step_val(x).map(|x| Val::Exp(Box::new(x))),
+            Val::Ln(x) => self.step_val(x).map(|x| Val::Ln(Box::new(x))),
+            Val::Abs(x) => self.step_val(x).map(|x| Val::Abs(Box::new(x))),
+            Val::Sign(x) => self.step_val(x).map(|x| Val::Sign(Box::new(x))),
+            Val::Min(x, y) => self.step_pair(x, y).map(|(x, y)| Val::Min(x, y)),
+            Val::Max(x, y) => self.step_pair(x, y).map(|(x, y)| Val::Max(x, y)),
+            Val::If(c, x, y) => match self.step_cond(c) {
+                Some(c) => Some(Val::If(Box::new(c), x.clone(), y.clone())),
+                None => self.step_pair(x, y).map(|(x, y)| Val::If(c.clone(), x, y)),
+            },
+            Val::Int(_) | Val::Float(_) | Val::Param(_) => None,
+        };
+        match self.strategy {
+            Strategy::Innermost => sub.or_else(|| self.root_val(v)),
+            Strategy::Outermost => sub,
+        }
+    }
+
+    fn step_angle(&self, a: &Angle) -> Option<Angle> {
+        if self.strategy == Strategy::Outermost {
+            if let Some(b) = self.root_angle(a) {
+                return Some(b);
+            }
+        }
+        let bx = |v: Val| Box::new(v);
+        let sub = match a {
+            Angle::Pi(x) => self.step_val(x).map(|x| Angle::Pi(bx(x))),
+            Angle::Sum(x, y) => self.step_angles(x, y).map(|(x, y)| Angle::Sum(x, y)),
+            Angle::Dif(x, y) => self.step_angles(x, y).map(|(x, y)| Angle::Dif(x, y)),
+            Angle::Prd(x, y) => self.step_mixed(x, y).map(|(x, y)| Angle::Prd(x, y)),
+            Angle::Rat(x, y) => self.step_mixed(x, y).map(|(x, y)| Angle::Rat(x, y)),
+            Angle::ASin(x) => self.step_val(x).map(|x| Angle::ASin(bx(x))),
+            Angle::ACos(x) => self.step_val(x).map(|x| Angle::ACos(bx(x))),
+            Angle::ATan(x) => self.step_val(x).map(|x| Angle::ATan(bx(x))),
+            Angle::ATan2(x, y) => self.step_pair(x, y).map(|(x, y)| Angle::ATan2(x, y)),
+        };
+        match self.strategy {
+            Strategy::Innermost => sub.or_else(|| self.root_angle(a)),
+            Strategy::Outermost => sub,
+        }
+    }
+
+    /// Steps the leftmost rewritable value in a condition.
+    fn step_cond(&self, c: &Cond) -> Option<Cond> {
+        let bx = |c: Cond| Box::new(c);
+        match c {
+            Cond::Lt(x, y) => self.step_pair(x, y).map(|(x, y)| Cond::Lt(x, y)),
+            Cond::Le(x, y) => self.step_pair(x, y).map(|(x, y)| Cond::Le(x, y)),
+            Cond::Eq(x, y) => self.step_pair(x, y).map(|(x, y)| Cond::Eq(x, y)),
+            Cond::And(x, y) => match self.step_cond(x) {
+                Some(x) => Some(Cond::And(bx(x), y.clone())),
+                None => self.step_cond(y).map(|y| Cond::And(x.clone(), bx(y))),
+            },
+            Cond::Or(x, y) => match self.step_cond(x) {
+                Some(x) => Some(Cond::Or(bx(x), y.clone())),
+                None => self.step_cond(y).map(|y| Cond::Or(x.clone(), bx(y))),
+            },
+            Cond::Not(x) => self.step_cond(x).map(|x| Cond::Not(bx(x))),
+        }
+    }
+
+    /// Steps the leftmost rewritable child of a binary node.
+    fn step_pair(&self, x: &Val, y: &Val) -> Option<(Box<Val>, Box<Val>)> {
+        match self.step_val(x) {
+            Some(x) => Some((Box::new(x), Box::new(y.clone()))),
+            None => self.step_val(y).map(|y| (Box::new(x.clone()), Box::new(y))),
+        }
+    }
+
+    fn step_angles(&self, x: &Angle, y: &Angle) -> Option<(Box<Angle>, Box<Angle>)> {
+        match self.step_angle(x) {
+            Some(x) => Some((Box::new(x), Box::new(y.clone()))),
+            None => self
+                .step_angle(y)
+                .map(|y| (Box::new(x.clone()), Box::new(y))),
+        }
+    }
+
+    fn step_mixed(&self, x: &Angle, y: &Val) -> Option<(Box<Angle>, Box<Val>)> {
+        match self.step_angle(x) {
+            Some(x) => Some((Box::new(x), Box::new(y.clone()))),
+            None => self.step_val(y).map(|y| (Box::new(x.clone()), Box::new(y))),
+        }
+    }
+}
+
+impl Default for Rewriter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::phi;
+    use approx::assert_relative_eq;
+    use num_traits::ToPrimitive;
+
+    #[test]
+    fn test_golden_square() {
+        // phi^2 = phi + 1
+        let rw = Rewriter::new().rule(Rule::val(
+            Pat::from(phi()).pow(&2.into()),
+            Pat::from(phi()).add(&1.into()),
+        ));
+
+        let v = phi().ipow(2).mul(&Val::param(1));
+        let w = rw.rewrite(&v);
+        assert_eq!(
+            w,
+            Val::Prd(Box::new(phi().iadd(1)), Box::new(Val::param(1)))
+        );
+    }
+
+    #[test]
+    fn test_repeated_var() {
+        // x - x = 0
+        let rw = Rewriter::new().rule(Rule::val(Pat::var(0).sub(&Pat::var(0)), 0.into()));
+
+        let t = Val::param(1);
+        let v = Val::Dif(Box::new(t.clone()), Box::new(t.clone()));
+        assert_eq!(rw.rewrite(&v), Val::from(0));
+
+        let v = Val::Dif(Box::new(t.clone()), Box::new(Val::param(2)));
+        assert_eq!(rw.rewrite(&v), v);
+    }
+
+    #[test]
+    fn test_guard() {
+        // sqrt(x^2) = x, for x >= 0
+        let rw = Rewriter::new().rule(
+            Rule::val(Pat::var(0).pow(&2.into()).sqrt(), Pat::var(0))
+                .when(|b| b.val(0).to_f64().is_some_and(|x| x >= 0.0)),
+        );
+
+        let v = Val::from(3).pow(&2.into()).sqrt();
+        assert_eq!(rw.rewrite(&v), Val::from(3));
+
+        let v = Val::from(-3).pow(&2.into()).sqrt();
+        assert_eq!(rw.rewrite(&v), v);
+    }
+
+    #[test]
+    fn test_angle_rule() {
+        // acos(x) + asin(x) = pi/2
+        let rw = Rewriter::new().rule(Rule::angle(
+            Pat::var(0).acos().add(&Pat::var(0).asin()),
+            Angle::part(4).into(),
+        ));
+
+        let x = Val::from(1).idiv(3);
+        let v = x.acos().add(&x.asin()).sin();
+        assert_eq!(rw.rewrite(&v), Angle::part(4).sin());
+    }
+
+    #[test]
+    fn test_step_limit() {
+        // sqrt(5) = 2 phi - 1 doesn't terminate, since phi contains sqrt(5).
+        let sqrt5 = Val::from(5).sqrt();
+        let rw = Rewriter::new()
+            .rule(Rule::val(
+                sqrt5.clone().into(),
+                Pat::from(phi()).mul(&2.into()).sub(&1.into()),
+            ))
+            .max_steps(3);
+
+        let w = rw.rewrite(&sqrt5);
+        assert!(w.cost() > sqrt5.cost());
+        assert_relative_eq!(w.to_f64().unwrap(), sqrt5.to_f64().unwrap());
+    }
+
+    #[test]
+    fn test_strategy() {
+        // sqrt(x) = x^(1/2), applied once to sqrt(sqrt(2)).
+        let half = Pat::from(Val::from(1).idiv(2));
+        let rule = || Rule::val(Pat::var(0).sqrt(), Pat::var(0).pow(&half));
+        let v = Val::from(2).sqrt().sqrt();
+        let half = Val::from(1).idiv(2);
+
+        let inner = Rewriter::new().rule(rule()).max_steps(1);
+        assert_eq!(inner.rewrite(&v), Val::from(2).pow(&half).sqrt());
+
+        let outer = Rewriter::new()
+            .rule(rule())
+            .strategy(Strategy::Outermost)
+            .max_steps(1);
+        assert_eq!(outer.rewrite(&v), Val::from(2).sqrt().pow(&half));
+
+        // Both reach the same fixpoint.
+        let inner = inner.max_steps(10);
+        let outer = outer.max_steps(10);
+        assert_eq!(inner.rewrite(&v), outer.rewrite(&v));
+    }
+}
@@ -0,0 +1,545 @@
+use std::cmp::Ordering;
+use std::f64::consts::TAU;
+use std::fmt::Debug;
+
+use num_traits::ToPrimitive;
+
+use crate::interval::Interval;
+use crate::val::{Angle, Val};
+
+/// Number type that geometry can be built on.
+/// Implemented for exact `Val`, plain `f64`, certified `Interval` bounds and `Dual` gradients,
+/// so that the same construction code yields formulas, floats, bounds or derivatives.
+pub trait Scalar: Clone + Debug {
+    /// The matching angle type, e.g. `Angle` for `Val`; radians for the numeric backends.
+    type Angle: ScalarAngle<Self>;
+
+    fn int(a: i64) -> Self;
+
+    /// Converts an exact value, e.g. a constant like `phi()`.
+    fn from_val(a: &Val) -> Self;
+
+    fn add(&self, a: &Self) -> Self;
+    fn sub(&self, a: &Self) -> Self;
+    fn neg(&self) -> Self;
+    fn mul(&self, a: &Self) -> Self;
+    fn div(&self, a: &Self) -> Self;
+    fn sqrt(&self) -> Self;
+
+    /// Real cube root.
+    fn cbrt(&self) -> Self;
+
+    fn ipow(&self, a: i64) -> Self;
+
+    fn asin(&self) -> Self::Angle;
+    fn acos(&self) -> Self::Angle;
+    fn atan(&self) -> Self::Angle;
+
+    /// Four-quadrant arctangent of `self / x`.
+    fn atan2(&self, x: &Self) -> Self::Angle;
+
+    /// Checks if the value is known to be exactly zero.
+    fn is_zero(&self) -> bool;
+
+    /// Rewrites the value into a simpler form; only does something for symbolic backends.
+    fn simplify(&self) -> Self {
+        self.clone()
+    }
+}
+
+/// Angle type of a `Scalar` backend.
+pub trait ScalarAngle<S>: Clone + Debug {
+    fn zero() -> Self;
+
+    /// A full turn.
+    fn turn() -> Self;
+
+    /// Converts an exact angle, e.g. a constant like `alpha()`.
+    fn from_angle(a: &Angle) -> Self;
+
+    fn add(&self, a: &Self) -> Self;
+    fn sub(&self, a: &Self) -> Self;
+    fn neg(&self) -> Self;
+    fn mul(&self, a: &S) -> Self;
+    fn imul(&self, a: i64) -> Self;
+    fn idiv(&self, a: i64) -> Self;
+
+    fn sin(&self) -> S;
+    fn cos(&self) -> S;
+
+    /// Checks if the angle is known to be exactly zero.
+    fn is_zero(&self) -> bool;
+
+    /// Reduces the angle into [0, 2pi), where that can be done.
+    fn reduce(&self) -> Self;
+
+    /// Compares angles, only returning an ordering that is certain.
+    /// For the float backends, that is one that rounding errors can't have flipped.
+    fn cmp_certified(&self, a: &Self) -> Option<Ordering>;
+
+    fn simplify(&self) -> Self {
+        self.clone()
+    }
+}
+
+impl Scalar for Val {
+    type Angle = Angle;
+
+    fn int(a: i64) -> Self {
+        a.into()
+    }
+
+    fn from_val(a: &Val) -> Self {
+        a.clone()
+    }
+
+    fn add(&self, a: &Self) -> Self {
+        Val::add(self, a)
+    }
+
+    fn sub(&self, a: &Self) -> Self {
+        Val::sub(self, a)
+    }
+
+    fn neg(&self) -> Self {
+        Val::neg(self)
+    }
+
+    fn mul(&self, a: &Self) -> Self {
+        Val::mul(self, a)
+    }
+
+    fn div(&self, a: &Self) -> Self {
+        Val::div(self, a)
+    }
+
+    fn sqrt(&self) -> Self {
+        Val::sqrt(self)
+    }
+
+    /// sign(x)·|x|^(1/3), since fractional powers are only taken of non-negative values.
+    fn cbrt(&self) -> Self {
+        self.sign().mul(&self.abs().pow(&Val::from(1).idiv(3)))
+    }
+
+    fn ipow(&self, a: i64) -> Self {
+        Val::ipow(self, a)
+    }
+
+    fn asin(&self) -> Angle {
+        Val::asin(self)
+    }
+
+    fn acos(&self) -> Angle {
+        Val::acos(self)
+    }
+
+    fn atan(&self) -> Angle {
+        Val::atan(self)
+    }
+
+    fn atan2(&self, x: &Self) -> Angle {
+        Val::atan2(self, x)
+    }
+
+    fn is_zero(&self) -> bool {
+        Val::is_zero(self)
+    }
+
+    fn simplify(&self) -> Self {
+        self.simplify_trig()
+    }
+}
+
+impl ScalarAngle<Val> for Angle {
+    fn zero() -> Self {
+        Angle::zero()
+    }
+
+    fn turn() -> Self {
+        Angle::turn()
+    }
+
+    fn from_angle(a: &Angle) -> Self {
+        a.clone()
+    }
+
+    fn add(&self, a: &Self) -> Self {
+        Angle::add(self, a)
+    }
+
+    fn sub(&self, a: &Self) -> Self {
+        Angle::sub(self, a)
+    }
+
+    fn neg(&self) -> Self {
+        Angle::neg(self)
+    }
+
+    fn mul(&self, a: &Val) -> Self {
+        Angle::mul(self, a)
+    }
+
+    fn imul(&self, a: i64) -> Self {
+        Angle::imul(self, a)
+    }
+
+    fn idiv(&self, a: i64) -> Self {
+        Angle::idiv(self, a)
+    }
+
+    fn sin(&self) -> Val {
+        Angle::sin(self)
+    }
+
+    fn cos(&self) -> Val {
+        Angle::cos(self)
+    }
+
+    fn is_zero(&self) -> bool {
+        Angle::is_zero(self)
+    }
+
+    fn reduce(&self) -> Self {
+        Angle::reduce(self)
+    }
+
+    fn cmp_certified(&self, a: &Self) -> Option<Ordering> {
+        Angle::cmp_certified(self, a)
+    }
+
+    fn simplify(&self) -> Self {
+        self.simplify_trig()
+    }
+}
+
+impl Scalar for f64 {
+    type Angle = f64;
+
+    fn int(a: i64) -> Self {
+        a as f64
+    }
+
+    /// NaN for values that can't be evaluated, e.g. parameters.
+    fn from_val(a: &Val) -> Self {
+        a.to_f64().unwrap_or(f64::NAN)
+    }
+
+    fn add(&self, a: &Self) -> Self {
+        self + a
+    }
+
+    fn sub(&self, a: &Self) -> Self {
+        self - a
+    }
+
+    fn neg(&self) -> Self {
+        -self
+    }
+
+    fn mul(&self, a: &Self) -> Self {
+        self * a
+    }
+
+    fn div(&self, a: &Self) -> Self {
+        self / a
+    }
+
+    fn sqrt(&self) -> Self {
+        f64::sqrt(*self)
+    }
+
+    fn cbrt(&self) -> Self {
+        f64::cbrt(*self)
+    }
+
+    fn ipow(&self, a: i64) -> Self {
+        f64::powi(*self, a as i32)
+    }
+
+    fn asin(&self) -> Self {
+        f64::asin(*self)
+    }
+
+    fn acos(&self) -> Self {
+        f64::acos(*self)
+    }
+
+    fn atan(&self) -> Self {
+        f64::atan(*self)
+    }
+
+    fn atan2(&self, x: &Self) -> Self {
+        f64::atan2(*self, *x)
+    }
+
+    fn is_zero(&self) -> bool {
+        *self == 0.0
+    }
+}
+
+impl ScalarAngle<f64> for f64 {
+    fn zero() -> Self {
+        0.0
+    }
+
+    fn turn() -> Self {
+        TAU
+    }
+
+    fn from_angle(a: &Angle) -> Self {
+        a.to_f64().unwrap_or(f64::NAN)
+    }
+
+    fn add(&self, a: &Self) -> Self {
+        self + a
+    }
+
+    fn sub(&self, a: &Self) -> Self {
+        self - a
+    }
+
+    fn neg(&self) -> Self {
+        -self
+    }
+
+    fn mul(&self, a: &f64) -> Self {
+        self * a
+    }
+
+    fn imul(&self, a: i64) -> Self {
+        self * a as f64
+    }
+
+    fn idiv(&self, a: i64) -> Self {
+        self / a as f64
+    }
+
+    fn sin(&self) -> f64 {
+        f64::sin(*self)
+    }
+
+    fn cos(&self) -> f64 {
+        f64::cos(*self)
+    }
+
+    fn is_zero(&self) -> bool {
+        *self == 0.0
+    }
+
+    fn reduce(&self) -> Self {
+        self.rem_euclid(TAU)
+    }
+
+    fn cmp_certified(&self, a: &Self) -> Option<Ordering> {
+        cmp_rounded(*self, *a)
+    }
+}
+
+/// Compares floats, unless they are close enough for rounding errors to have flipped the order.
+pub(crate) fn cmp_rounded(a: f64, b: f64) -> Option<Ordering> {
+    let tolerance = 64.0 * f64::EPSILON * a.abs().max(b.abs()).max(1.0);
+    if (a - b).abs() <= tolerance {
+        None
+    } else {
+        a.partial_cmp(&b)
+    }
+}
+
+/// Operations without finite bounds yield the whole real line.
+fn entire() -> Interval {
+    Interval::new(f64::NEG_INFINITY, f64::INFINITY)
+}
+
+impl Scalar for Interval {
+    type Angle = Interval;
+
+    fn int(a: i64) -> Self {
+        Val::from(a).to_interval().unwrap()
+    }
+
+    /// The whole real line for values that can't be bounded, e.g. parameters.
+    fn from_val(a: &Val) -> Self {
+        a.to_interval().unwrap_or_else(entire)
+    }
+
+    fn add(&self, a: &Self) -> Self {
+        Interval::add(self, a)
+    }
+
+    fn sub(&self, a: &Self) -> Self {
+        Interval::sub(self, a)
+    }
+
+    fn neg(&self) -> Self {
+        Interval::neg(self)
+    }
+
+    fn mul(&self, a: &Self) -> Self {
+        Interval::mul(self, a)
+    }
+
+    fn div(&self, a: &Self) -> Self {
+        Interval::div(self, a).unwrap_or_else(entire)
+    }
+
+    fn sqrt(&self) -> Self {
+        Interval::sqrt(self).unwrap_or_else(entire)
+    }
+
+    fn cbrt(&self) -> Self {
+        Interval::cbrt(self)
+    }
+
+    fn ipow(&self, a: i64) -> Self {
+        self.pow(&Self::int(a)).unwrap_or_else(entire)
+    }
+
+    fn asin(&self) -> Self {
+        Interval::asin(self).unwrap_or_else(entire)
+    }
+
+    fn acos(&self) -> Self {
+        Interval::acos(self).unwrap_or_else(entire)
+    }
+
+    fn atan(&self) -> Self {
+        Interval::atan(self)
+    }
+
+    /// Angles in (-pi, pi], so the whole line where the branch cut may be crossed.
+    fn atan2(&self, x: &Self) -> Self {
+        Interval::atan2(self, x).unwrap_or_else(entire)
+    }
+
+    fn is_zero(&self) -> bool {
+        *self == Self::point(0.0)
+    }
+}
+
+impl ScalarAngle<Interval> for Interval {
+    fn zero() -> Self {
+        Self::point(0.0)
+    }
+
+    fn turn() -> Self {
+        Self::pi().mul(&Self::point(2.0))
+    }
+
+    fn from_angle(a: &Angle) -> Self {
+        a.to_interval().unwrap_or_else(entire)
+    }
+
+    fn add(&self, a: &Self) -> Self {
+        Interval::add(self, a)
+    }
+
+    fn sub(&self, a: &Self) -> Self {
+        Interval::sub(self, a)
+    }
+
+    fn neg(&self) -> Self {
+        Interval::neg(self)
+    }
+
+    fn mul(&self, a: &Interval) -> Self {
+        Interval::mul(self, a)
+    }
+
+    fn imul(&self, a: i64) -> Self {
+        Interval::mul(self, &Scalar::int(a))
+    }
+
+    fn idiv(&self, a: i64) -> Self {
+        Scalar::div(self, &Scalar::int(a))
+    }
+
+    fn sin(&self) -> Interval {
+        Interval::sin(self)
+    }
+
+    fn cos(&self) -> Interval {
+        Interval::cos(self)
+    }
+
+    fn is_zero(&self) -> bool {
+        *self == Self::point(0.0)
+    }
+
+    /// Shifts by whole turns, if both bounds are within the same turn.
+    fn reduce(&self) -> Self {
+        let k = (self.lo() / TAU).floor();
+        if k == 0.0 || k != (self.hi() / TAU).floor() {
+            return *self;
+        }
+        self.sub(&ScalarAngle::imul(
+            &<Self as ScalarAngle<Self>>::turn(),
+            k as i64,
+        ))
+    }
+
+    fn cmp_certified(&self, a: &Self) -> Option<Ordering> {
+        Interval::cmp_certified(self, a)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::dual::Dual;
+    use Ordering::*;
+
+    #[test]
+    fn test_cmp_certified() {
+        let third = 1.0 / 3.0;
+        assert_eq!(ScalarAngle::cmp_certified(&1.0, &2.0), Some(Less));
+        assert_eq!(ScalarAngle::cmp_certified(&(third * 3.0), &1.0), None);
+        assert_eq!(ScalarAngle::cmp_certified(&(0.1 + 0.2), &0.3), None);
+        assert_eq!(ScalarAngle::cmp_certified(&f64::NAN, &0.0), None);
+        let (a, b) = (Dual::var(0.1 + 0.2), Dual::constant(0.3));
+        assert_eq!(a.cmp_certified(&b), None);
+        assert_eq!(a.cmp_certified(&Dual::constant(0.2)), Some(Greater));
+
+        let a = Interval::new(1.0, 2.0);
+        assert_eq!(a.cmp_certified(&Interval::new(2.5, 3.0)), Some(Less));
+        assert_eq!(a.cmp_certified(&Interval::new(1.5, 3.0)), None);
+        assert_eq!(
+            <Angle as ScalarAngle<Val>>::cmp_certified(&Angle::part(6), &Angle::part(4)),
+            Some(Less)
+        );
+    }
+
+    #[test]
+    fn test_cbrt() {
+        // Real cube roots, negative ones included, agree across the backends.
+        for x in [
+            Val::from(-8),
+            Val::from(27).idiv(8),
+            Val::from(2).sqrt().neg(),
+        ] {
+            let y = x.to_f64().unwrap();
+            let r = Scalar::cbrt(&x).to_f64().unwrap();
+            assert!((r - Scalar::cbrt(&y)).abs() < 1e-12, "{x:?}");
+            assert!(Interval::new(y, y).cbrt().contains(r), "{x:?}");
+        }
+        assert_eq!(Scalar::cbrt(&-8.0), -2.0);
+    }
+
+    #[test]
+    fn test_reduce() {
+        assert_eq!(ScalarAngle::reduce(&(-1.0)), TAU - 1.0);
+        let reduce = |lo: f64, hi: f64| ScalarAngle::reduce(&Interval::new(lo, hi));
+        // Already within the first turn.
+        assert_eq!(reduce(1.0, 2.0), Interval::new(1.0, 2.0));
+        // Across a whole turn, so there's nowhere to shift it.
+        assert_eq!(
+            reduce(TAU - 0.5, TAU + 0.5),
+            Interval::new(TAU - 0.5, TAU + 0.5)
+        );
+        for (lo, hi) in [(TAU + 1.0, TAU + 2.0), (1.0 - 2.0 * TAU, 2.0 - 2.0 * TAU)] {
+            let r = reduce(lo, hi);
+            assert!(r.contains(1.0) && r.contains(2.0));
+            assert!(r.lo() >= 0.0 && r.hi() < TAU);
+        }
+    }
+}
@@ -0,0 +1,130 @@
+use crate::mesh::Mesh;
+use crate::polygon::Polygon;
+use crate::spherical::Norm;
+use crate::val::Val;
+use crate::vec3::Vec3;
+
+/// Convex polyhedron with its vertices on the unit sphere.
+/// Faces list vertex indices counterclockwise when looking at the face from outside.
+/// Neighbours and other connectivity come from the underlying `Mesh`.
+#[derive(Clone)]
+pub struct Solid {
+    mesh: Mesh<Norm>,
+}
+
+impl Solid {
+    pub fn new(vertices: Vec<Norm>, faces: Vec<Vec<usize>>) -> Self {
+        Self {
+            mesh: Mesh::from_faces(vertices, &faces).expect("consistent winding"),
+        }
+    }
+
+    pub fn mesh(&self) -> &Mesh<Norm> {
+        &self.mesh
+    }
+
+    pub fn vertices(&self) -> &[Norm] {
+        self.mesh.positions()
+    }
+
+    /// Vertices as vectors.
+    pub fn vecs(&self) -> Vec<Vec3<Val>> {
+        self.vertices().iter().map(Vec3::from).collect()
+    }
+
+    pub fn faces(&self) -> Vec<Vec<usize>> {
+        self.mesh
+            .faces()
+            .map(|f| self.mesh.face_vertices(f))
+            .collect()
+    }
+
+    /// A face as a spherical polygon.
+    pub fn face(&self, f: usize) -> Polygon {
+        Polygon::new(
+            self.mesh
+                .face_vertices(f)
+                .iter()
+                .map(|&v| self.vertices()[v].clone())
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::dodec::dodecahedron;
+    use crate::icos::icosahedron;
+    use crate::testing::to_f64;
+    use approx::assert_relative_eq;
+    use num_traits::ToPrimitive;
+
+    fn check(s: &Solid, v: usize, e: usize, f: usize, degree: usize) {
+        let m = s.mesh();
+        assert_eq!(s.vertices().len(), v);
+        assert_eq!(m.edge_count(), e);
+        assert_eq!(s.faces().len(), f);
+        assert!(m.is_closed());
+
+        // Winding is outward.
+        let vecs: Vec<[f64; 3]> = s.vecs().iter().map(|v| to_f64(v).to_array()).collect();
+        for face in s.faces() {
+            let [a, b, c] = [0, 1, 2].map(|i| vecs[face[i]]);
+            let (u, w) = (
+                [0, 1, 2].map(|i| b[i] - a[i]),
+                [0, 1, 2].map(|i| c[i] - a[i]),
+            );
+            let n = [
+                u[1] * w[2] - u[2] * w[1],
+                u[2] * w[0] - u[0] * w[2],
+                u[0] * w[1] - u[1] * w[0],
+            ];
+            assert!(n[0] * a[0] + n[1] * a[1] + n[2] * a[2] > 0.0);
+        }
+
+        for v in m.vertices() {
+            assert_eq!(m.vertex_neighbours(v).len(), degree);
+        }
+
+        // All edges subtend the same angle.
+        let angle = |h: usize| {
+            let (a, b) = (m.half_edge(h).origin, m.dest(h));
+            s.vertices()[a].angle_to(&s.vertices()[b]).to_f64().unwrap()
+        };
+        let by = angle(m.edges().next().unwrap());
+        for h in m.edges() {
+            assert_relative_eq!(angle(h), by, epsilon = 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_icosahedron() {
+        let s = icosahedron();
+        check(&s, 12, 30, 20, 5);
+        assert_eq!(s.mesh().vertex_neighbours(0), vec![1, 2, 3, 4, 5]);
+        assert_eq!(s.mesh().vertex_faces(0), vec![0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_dodecahedron() {
+        let d = dodecahedron();
+        check(&d, 20, 30, 12, 3);
+
+        // The dual of the icosahedron: vertices are the face centres.
+        let s = icosahedron();
+        for (f, v) in d.vertices().iter().enumerate() {
+            assert_relative_eq!(
+                s.face(f).centroid().angle_to(v).to_f64().unwrap(),
+                0.0,
+                epsilon = 1e-7
+            );
+        }
+        for (v, face) in d.faces().iter().enumerate() {
+            let mut around = s.mesh().vertex_faces(v);
+            let i = around.iter().position(|&f| f == face[0]).unwrap();
+            around.rotate_left(i);
+            assert_eq!(&around, face);
+        }
+    }
+}
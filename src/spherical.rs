@@ -1,166 +1,201 @@
-use crate::val::{Angle, Val};
+use std::cmp::Ordering;
+
+use crate::quat::Quat;
+use crate::scalar::{Scalar, ScalarAngle};
+use crate::val::Val;
+use crate::vec3::Vec3;
 
 /// Normalised spherical coordinates (r = 1).
 /// Uses the physics convention (ISO 80000-2:2019).
-#[derive(Clone)]
-pub struct Norm {
+/// Generic over the number type, see `Scalar`; symbolic by default.
+#[derive(Clone, Debug)]
+pub struct Norm<S: Scalar = Val> {
     /// Polar angle, with respect to positive polar axis "z" [0-pi].
-    theta: Angle,
+    theta: S::Angle,
 
     /// Azimuthal angle, rotation from the initial meridian plane "xz" [0-2pi].
-    phi: Angle,
+    phi: S::Angle,
 }
 
-impl Norm {
+impl<S: Scalar> Norm<S> {
     pub fn zero() -> Self {
         Self {
-            theta: Angle::zero(),
-            phi: Angle::zero(),
+            theta: S::Angle::zero(),
+            phi: S::Angle::zero(),
         }
     }
 
-    pub fn north(&self, a: &Angle) -> Self {
-        self.south(&a.clone().neg())
+    /// Point at polar angle `theta` and azimuth `phi`.
+    pub fn new(theta: S::Angle, phi: S::Angle) -> Self {
+        Self::canonical(theta, phi)
     }
 
-    pub fn south(&self, a: &Angle) -> Self {
-        Self {
-            theta: self.theta.add(a),
-            phi: self.phi.clone(),
-        }
+    pub fn north(&self, a: &S::Angle) -> Self {
+        self.south(&a.neg())
     }
 
-    pub fn east(&self, a: &Angle) -> Self {
-        Self {
-            theta: self.theta.clone(),
-            phi: self.phi.add(a),
-        }
+    pub fn south(&self, a: &S::Angle) -> Self {
+        Self::canonical(self.theta.add(a), self.phi.clone())
     }
 
-    pub fn west(&self, a: &Angle) -> Self {
-        self.east(&a.clone().neg())
+    pub fn east(&self, a: &S::Angle) -> Self {
+        Self::canonical(self.theta.clone(), self.phi.add(a))
     }
 
-    pub fn rot_x(&self, by: &Angle) -> Self {
+    pub fn west(&self, a: &S::Angle) -> Self {
+        self.east(&a.neg())
+    }
+
+    /// Brings the polar angle into [0, pi] by crossing the poles, and the azimuth into [0, 2pi).
+    /// Only done where the comparisons can be certified, e.g. not for parametric angles.
+    fn canonical(theta: S::Angle, phi: S::Angle) -> Self {
+        let turn = S::Angle::turn();
+        let half = turn.idiv(2);
+        let mut theta = theta.reduce();
+        let mut phi = phi;
+        if theta.cmp_certified(&S::Angle::zero()) == Some(Ordering::Less) {
+            theta = theta.neg();
+            phi = phi.add(&half);
+        }
+        if theta.cmp_certified(&half) == Some(Ordering::Greater)
+            && theta.cmp_certified(&turn) == Some(Ordering::Less)
+        {
+            theta = turn.sub(&theta);
+            phi = phi.add(&half);
+        }
         Self {
-            theta: self
-                .theta
-                .sin()
-                .mul(&self.phi.sin())
-                .mul(&by.sin())
-                .add(&self.theta.cos().mul(&by.cos()))
-                .acos(),
-            phi: self
-                .phi
-                .tan()
-                .mul(
-                    &by.cos().sub(
-                        &self
-                            .phi
-                            .tan()
-                            .rec()
-                            .mul(&by.sin())
-                            .mul(&self.phi.cos().rec()),
-                    ),
-                )
-                .atan(),
+            theta: theta.reduce(),
+            phi: phi.reduce(),
         }
     }
 
-    /// Rotate using quaternion multiplications.
-    pub fn rot(&self, axis: &Self, by: &Angle) -> Self {
+    pub fn theta(&self) -> &S::Angle {
+        &self.theta
+    }
+
+    pub fn phi(&self) -> &S::Angle {
+        &self.phi
+    }
+
+    /// Rotates about the x axis, counterclockwise when looking towards the origin.
+    pub fn rot_x(&self, by: &S::Angle) -> Self {
+        let (sin, cos) = (by.sin(), by.cos());
+        let (y, z) = (self.y(), self.z());
+        Vec3::new(
+            self.x(),
+            y.mul(&cos).sub(&z.mul(&sin)),
+            y.mul(&sin).add(&z.mul(&cos)),
+        )
+        .to_norm()
+    }
+
+    /// Rotates counterclockwise about `axis`, when looking towards the origin.
+    pub fn rot(&self, axis: &Self, by: &S::Angle) -> Self {
         if by.is_zero() {
             return self.clone();
         }
+        Quat::from_axis_angle(axis, by).apply(self)
+    }
 
-        // The point to rotate.
-        // Note the negative z value, to preserve z-up axis.
-        let p = [Val::from(0), self.x(), self.y(), self.z().neg()];
-
-        let sin = by.idiv(2).sin();
-        // Te quaternion to rotate by:
-        let q = [
-            by.idiv(2).cos(),
-            axis.x().mul(&sin),
-            axis.y().mul(&sin),
-            axis.z().mul(&sin),
-        ];
-        // The inverse of the rotation quaternion:
-        let qn = [q[0].clone(), q[1].neg(), q[2].neg(), q[3].neg()];
-
-        // Result of multiplying q with p (considering p[0] == 0):
-        let qp = [
-            (q[1].mul(&p[1]).neg())
-                .sub(&q[2].mul(&p[2]))
-                .sub(&q[3].mul(&p[3])),
-            q[0].mul(&p[1]).sub(&q[2].mul(&p[3])).add(&q[3].mul(&p[2])),
-            q[0].mul(&p[2]).add(&q[1].mul(&p[3])).sub(&q[3].mul(&p[1])),
-            q[0].mul(&p[3]).sub(&q[1].mul(&p[2])).add(&q[2].mul(&p[1])),
-        ];
+    /// The point on the opposite side of the sphere.
+    pub fn antipode(&self) -> Self {
+        let half = S::Angle::turn().idiv(2);
+        Self::canonical(half.sub(&self.theta), self.phi.add(&half))
+    }
 
-        // Result of multiplying qp with pn:
-        let qpqn = [
-            Val::from(0),
-            // I.e. the result of:
-            // (qp[0].mul(&qn[0]))
-            //     .sub(&qp[1].mul(&qn[1]))
-            //     .sub(&qp[2].mul(&qn[2]))
-            //     .sub(&qp[3].mul(&qn[3])),
-            (qp[0].mul(&qn[1]))
-                .add(&qp[1].mul(&qn[0]))
-                .sub(&qp[2].mul(&qn[3]))
-                .add(&qp[3].mul(&qn[2])),
-            (qp[0].mul(&qn[2]))
-                .add(&qp[1].mul(&qn[3]))
-                .add(&qp[2].mul(&qn[0]))
-                .sub(&qp[3].mul(&qn[1])),
-            (qp[0].mul(&qn[3]))
-                .add(&qp[1].mul(&qn[2]))
-                .add(&qp[2].mul(&qn[1]))
-                .sub(&qp[3].mul(&qn[0])),
-        ];
+    /// Angular (great-circle) distance, in [0, pi].
+    pub fn angle_to(&self, to: &Self) -> S::Angle {
+        let (a, b) = (Vec3::from(self), Vec3::from(to));
+        let sin = a.cross(&b).simplify().norm();
+        sin.atan2(&a.dot(&b).simplify())
+    }
 
-        // Convert back to axis-angle:
-        Self {
-            theta: qpqn[3]
-                .div(
-                    &(qpqn[1].ipow(2))
-                        .add(&qpqn[2].ipow(2))
-                        .add(&qpqn[3].ipow(2))
-                        .sqrt(),
-                )
-                .acos(),
-            phi: qpqn[1]
-                .div(&(qpqn[1].ipow(2)).add(&qpqn[2].ipow(2)).sqrt())
-                .acos(),
-        }
+    /// Initial bearing of the great circle towards `to`, clockwise from north (i.e. the positive z axis).
+    /// Undefined on the poles and between antipodes.
+    pub fn bearing_to(&self, to: &Self) -> S::Angle {
+        let d = to.phi.sub(&self.phi);
+        let y = d.sin().mul(&to.theta.sin());
+        let x = self
+            .theta
+            .sin()
+            .mul(&to.theta.cos())
+            .sub(&self.theta.cos().mul(&to.theta.sin()).mul(&d.cos()));
+        y.simplify().atan2(&x.simplify()).simplify()
+    }
+
+    /// Point halfway along the shorter great-circle arc; undefined between antipodes.
+    pub fn midpoint(&self, to: &Self) -> Self {
+        Vec3::from(self).add(&to.into()).simplify().to_norm()
+    }
+
+    /// Spherical linear interpolation along the shorter great-circle arc, `t` in [0-1].
+    pub fn slerp(&self, to: &Self, t: &S) -> Self {
+        let by = self.angle_to(to);
+        // The common 1/sin(by) factor is dropped, as the length doesn't matter.
+        Vec3::from(self)
+            .scale(&by.mul(&S::int(1).sub(t)).sin())
+            .add(&Vec3::from(to).scale(&by.mul(t).sin()))
+            .simplify()
+            .to_norm()
     }
 
-    pub fn distance_to(self, to: Self) -> Val {
+    /// Divides the arc towards `to` into `n` equal parts, returning the `n + 1` points.
+    pub fn subdivide(&self, to: &Self, n: i64) -> Vec<Self> {
+        (0..=n)
+            .map(|i| match i {
+                0 => self.clone(),
+                i if i == n => to.clone(),
+                i => self.slerp(to, &S::int(i).div(&S::int(n))),
+            })
+            .collect()
+    }
+
+    pub fn distance_to(self, to: Self) -> S {
         let dx = to.x().sub(&self.x()).ipow(2);
         let dy = to.y().sub(&self.y()).ipow(2);
         let dz = to.z().sub(&self.z()).ipow(2);
         dx.add(&dy).add(&dz).sqrt()
     }
 
-    pub fn x(&self) -> Val {
+    pub fn x(&self) -> S {
         self.theta.sin().mul(&self.phi.cos())
     }
 
-    pub fn y(&self) -> Val {
+    pub fn y(&self) -> S {
         self.theta.sin().mul(&self.phi.sin())
     }
 
-    pub fn z(&self) -> Val {
+    pub fn z(&self) -> S {
         self.theta.cos()
     }
 }
 
+impl Norm {
+    /// Collapses parameter-free subtrees of both angles, see `Val::approximate`.
+    pub fn approximate(&self, cost: usize) -> Self {
+        Self {
+            theta: self.theta.approximate(cost),
+            phi: self.phi.approximate(cost),
+        }
+    }
+
+    /// The same point on another backend, e.g. to evaluate an exact construction with floats.
+    pub fn to_scalar<S: Scalar>(&self) -> Norm<S> {
+        Norm::new(
+            S::Angle::from_angle(&self.theta),
+            S::Angle::from_angle(&self.phi),
+        )
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::alpha;
+    use crate::val::Angle;
     use approx::assert_relative_eq;
     use num_traits::ToPrimitive;
+    use std::f64::consts::PI;
 
     #[test]
     fn test_distance_to() {
@@ -169,13 +204,16 @@ mod test {
 
         // Zero distance.
         assert_relative_eq!(
-            Norm::zero().distance_to(Norm::zero()).to_f64().unwrap(),
+            Norm::<Val>::zero()
+                .distance_to(Norm::zero())
+                .to_f64()
+                .unwrap(),
             0.0
         );
 
         // Distance between north & south poles.
         assert_relative_eq!(
-            Norm::zero()
+            Norm::<Val>::zero()
                 .distance_to(Norm::zero().south(&half_turn))
                 .to_f64()
                 .unwrap(),
@@ -184,7 +222,7 @@ mod test {
 
         // Distance between two opposite points on the equator.
         assert_relative_eq!(
-            Norm::zero()
+            Norm::<Val>::zero()
                 .south(&quarter_turn)
                 .distance_to(Norm::zero().south(&quarter_turn).west(&half_turn))
                 .to_f64()
@@ -194,7 +232,7 @@ mod test {
 
         // Distance between any point on the equator and any pole.
         assert_relative_eq!(
-            Norm::zero()
+            Norm::<Val>::zero()
                 .south(&half_turn)
                 .distance_to(Norm::zero().south(&quarter_turn))
                 .to_f64()
@@ -202,4 +240,207 @@ mod test {
             Val::from(2).sqrt().to_f64().unwrap()
         );
     }
+
+    #[test]
+    fn test_great_circle() {
+        let pole: Norm = Norm::zero();
+        let a = Norm::zero().south(&Angle::part(4));
+        let b = a.east(&Angle::part(4));
+
+        assert_eq!(
+            pole.angle_to(&a).to_rational(),
+            Angle::part(4).to_rational()
+        );
+        assert_eq!(
+            a.angle_to(&a.antipode()).to_rational(),
+            Angle::part(2).to_rational()
+        );
+        assert_relative_eq!(
+            a.angle_to(&Norm::zero().south(&alpha())).to_f64().unwrap(),
+            Angle::part(4).sub(&alpha()).to_f64().unwrap(),
+            epsilon = 1e-12
+        );
+
+        assert_eq!(
+            a.bearing_to(&pole).to_rational(),
+            Angle::zero().to_rational()
+        );
+        assert_eq!(a.bearing_to(&b).to_rational(), Angle::part(4).to_rational());
+        assert_eq!(
+            b.bearing_to(&a).to_rational(),
+            Angle::part(4).imul(-1).to_rational()
+        );
+
+        let m = a.midpoint(&b);
+        assert_eq!(m.phi().to_rational(), Angle::part(8).to_rational());
+        assert_eq!(m.theta().to_rational(), Angle::part(4).to_rational());
+
+        // Slerp moves at constant speed.
+        let c = Norm::zero().south(&alpha()).east(&Angle::part(5));
+        let by = a.angle_to(&c).to_f64().unwrap();
+        let points = a.subdivide(&c, 3);
+        assert_eq!(points.len(), 4);
+        for p in points.windows(2) {
+            assert_relative_eq!(
+                p[0].angle_to(&p[1]).to_f64().unwrap(),
+                by / 3.0,
+                epsilon = 1e-12
+            );
+        }
+        assert_relative_eq!(
+            a.slerp(&c, &Val::from(1).idiv(2))
+                .angle_to(&a.midpoint(&c))
+                .to_f64()
+                .unwrap(),
+            0.0,
+            epsilon = 1e-7
+        );
+    }
+
+    #[test]
+    fn test_canonical() {
+        let quarter_turn = Angle::part(4);
+
+        // Crossing the north pole flips to the opposite meridian.
+        let p: Norm = Norm::zero().east(&quarter_turn).north(&quarter_turn);
+        assert_eq!(p.theta().to_rational(), quarter_turn.to_rational());
+        assert_eq!(p.phi().to_rational(), Angle::part(4).imul(3).to_rational());
+
+        // Longitudes wrap around.
+        let p: Norm = Norm::zero().south(&quarter_turn);
+        let p = (0..7).fold(p, |p, _| p.east(&Angle::part(5)));
+        assert_eq!(p.phi().to_rational(), Angle::part(5).imul(2).to_rational());
+
+        // Crossing the south pole, with an irrational angle.
+        let a = Val::from(1).idiv(3).acos();
+        let p: Norm = Norm::zero().south(&Angle::turn().idiv(2)).south(&a);
+        assert_relative_eq!(p.theta().to_f64().unwrap(), PI - a.to_f64().unwrap());
+        assert_eq!(p.phi().to_rational(), Angle::turn().idiv(2).to_rational());
+        assert_relative_eq!(p.z().to_f64().unwrap(), -1.0 / 3.0, epsilon = 1e-15);
+
+        // Parametric angles are left alone.
+        let t = Val::param(1).pi();
+        let p: Norm = Norm::zero().north(&t);
+        assert_eq!(p.theta(), &t.neg());
+    }
+
+    /// Rotates `v` about the unit vector `k` by `a` radians (Rodrigues' formula).
+    fn rodrigues(v: [f64; 3], k: [f64; 3], a: f64) -> [f64; 3] {
+        let dot = v[0] * k[0] + v[1] * k[1] + v[2] * k[2];
+        let cross = [
+            k[1] * v[2] - k[2] * v[1],
+            k[2] * v[0] - k[0] * v[2],
+            k[0] * v[1] - k[1] * v[0],
+        ];
+        [0, 1, 2].map(|i| v[i] * a.cos() + cross[i] * a.sin() + k[i] * dot * (1.0 - a.cos()))
+    }
+
+    fn to_f64(n: &Norm) -> [f64; 3] {
+        [n.x(), n.y(), n.z()].map(|v| v.to_f64().unwrap())
+    }
+
+    /// A point in each octant, plus the poles.
+    fn octants() -> Vec<Norm> {
+        let mut points = vec![Norm::zero(), Norm::zero().south(&Angle::part(2))];
+        for theta in [1, 3] {
+            for phi in [1, 3, 5, 7] {
+                points.push(
+                    Norm::zero()
+                        .south(&Angle::part(8).imul(theta))
+                        .east(&Angle::part(8).imul(phi)),
+                );
+            }
+        }
+        points
+    }
+
+    #[test]
+    fn test_rot() {
+        let axes = [
+            Norm::zero(),
+            Norm::zero().south(&Angle::part(4)),
+            Norm::zero().south(&Angle::part(4)).east(&Angle::part(4)),
+            Norm::zero()
+                .south(&Val::from(1).idiv(3).acos())
+                .east(&Angle::part(8).imul(5)),
+        ];
+        for axis in axes.iter() {
+            for by in [Angle::part(3), Angle::part(8).imul(-3), Angle::part(2)] {
+                for p in octants() {
+                    let want = rodrigues(to_f64(&p), to_f64(axis), by.to_f64().unwrap());
+                    let got = to_f64(&p.rot(axis, &by));
+                    for i in 0..3 {
+                        assert_relative_eq!(got[i], want[i], epsilon = 1e-12);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_rot_x() {
+        let x = Norm::zero().south(&Angle::part(4));
+        for by in [Angle::part(6), Angle::part(8).imul(-3), Angle::part(2)] {
+            for p in octants() {
+                let want = rodrigues(to_f64(&p), [1.0, 0.0, 0.0], by.to_f64().unwrap());
+                let got = to_f64(&p.rot_x(&by));
+                for i in 0..3 {
+                    assert_relative_eq!(got[i], want[i], epsilon = 1e-12);
+                }
+                let got = to_f64(&p.rot(&x, &by));
+                for i in 0..3 {
+                    assert_relative_eq!(got[i], want[i], epsilon = 1e-12);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_rot_poles() {
+        // Rotating the equator onto the north pole.
+        let p: Norm = Norm::zero().south(&Angle::part(4)).east(&Angle::part(4));
+        let n = p.rot_x(&Angle::part(4));
+        assert_relative_eq!(n.z().to_f64().unwrap(), 1.0);
+        assert_eq!(n.phi().to_f64(), Some(0.0));
+    }
+
+    /// The same construction on every backend.
+    fn construct<S: Scalar>(a: &Norm<S>, t: &S) -> (Norm<S>, S::Angle) {
+        let b = a.east(&S::Angle::turn().idiv(5));
+        let p = a.slerp(&b, t).rot_x(&S::Angle::turn().idiv(8));
+        let d = p.angle_to(&Norm::zero());
+        (p, d)
+    }
+
+    #[test]
+    fn test_backends() {
+        use crate::{Dual, Interval};
+
+        let a: Norm = Norm::zero().south(&alpha());
+        let (p, d) = construct(&a, &Val::from(1).idiv(3));
+        let (q, e) = construct(&a.to_scalar::<f64>(), &(1.0 / 3.0));
+        assert_relative_eq!(d.to_f64().unwrap(), e, epsilon = 1e-12);
+        let want = [q.x(), q.y(), q.z()];
+        for (x, y) in to_f64(&p).iter().zip(want.iter()) {
+            assert_relative_eq!(x, y, epsilon = 1e-12);
+        }
+
+        // Certified bounds contain the float result.
+        let third = Interval::from_val(&Val::from(1).idiv(3));
+        let (r, f) = construct(&a.to_scalar::<Interval>(), &third);
+        assert!(f.contains(e) && f.width() < 1e-9, "{:?}", f);
+        for (i, x) in [r.x(), r.y(), r.z()].iter().zip(want.iter()) {
+            assert!(i.contains(*x) && i.width() < 1e-9, "{:?} {}", i, x);
+        }
+
+        // Derivatives match finite differences.
+        let h = 1e-6;
+        let (s, g) = construct(&a.to_scalar::<Dual>(), &Dual::var(1.0 / 3.0));
+        let (lo, hi) = (
+            construct(&a.to_scalar::<f64>(), &(1.0 / 3.0 - h)),
+            construct(&a.to_scalar::<f64>(), &(1.0 / 3.0 + h)),
+        );
+        assert_relative_eq!(g.d(), (hi.1 - lo.1) / (2.0 * h), epsilon = 1e-6);
+        assert_relative_eq!(s.z().d(), (hi.0.z() - lo.0.z()) / (2.0 * h), epsilon = 1e-6);
+    }
 }
@@ -0,0 +1,825 @@
+use std::collections::{BTreeSet, HashMap};
+use std::sync::LazyLock;
+
+use crate::mesh::{trace_faces, Mesh};
+use crate::platonic::Platonic;
+use crate::vec3::Vec3;
+
+/// Cells of the stellated icosahedron, in Du Val's notation, as used by Coxeter et al. and by Crennell.
+///
+/// Layers A to H are by the number of face planes crossed to get there from the centre;
+/// where a layer has several kinds of cells, they are told apart by subscripts.
+/// The 120 cells of f1 are two sets of 60 that are mirror images of each other, f1 and f1'.
+const LABELS: [&str; 12] = [
+    "a", "b", "c", "d", "e1", "e2", "f1", "f1'", "f2", "g1", "g2", "h",
+];
+
+/// The 59 stellations in the order of Crennell's numbering, which follows the table of Coxeter et al.
+const CRENNELL: [&str; 59] = [
+    "A",
+    "B",
+    "C",
+    "D",
+    "E",
+    "F",
+    "G",
+    "H",
+    "e1",
+    "f1",
+    "g1",
+    "e1f1",
+    "e1f1g1",
+    "f1g1",
+    "e2",
+    "f2",
+    "g2",
+    "e2f2",
+    "e2f2g2",
+    "f2g2",
+    "De1",
+    "Ef1",
+    "Fg1",
+    "De1f1",
+    "De1f1g1",
+    "Ef1g1",
+    "De2",
+    "Ef2",
+    "Fg2",
+    "De2f2",
+    "De2f2g2",
+    "Ef2g2",
+    "f1'",
+    "e1f1'",
+    "De1f1'",
+    "f1'g1",
+    "e1f1'g1",
+    "De1f1'g1",
+    "f1'g2",
+    "e1f1'g2",
+    "De1f1'g2",
+    "f1'f2g2",
+    "e1f1'f2g2",
+    "De1f1'f2g2",
+    "e2f1'",
+    "De2f1'",
+    "Ef1'",
+    "e2f1'g1",
+    "De2f1'g1",
+    "Ef1'g1",
+    "e2f1'f2",
+    "De2f1'f2",
+    "Ef1'f2",
+    "e2f1'f2g1",
+    "De2f1'f2g1",
+    "Ef1'f2g1",
+    "e2f1'f2g2",
+    "De2f1'f2g2",
+    "Ef1'f2g2",
+];
+
+/// Layer of each kind of cell, from 0 for A.
+const LAYERS: [usize; 12] = [0, 1, 2, 3, 4, 4, 5, 5, 5, 6, 6, 7];
+
+/// The cells in each kind, for telling them apart within a layer.
+const SIZES: [usize; 12] = [1, 20, 30, 60, 20, 60, 60, 60, 12, 30, 60, 60];
+
+/// The two halves of f1.
+const CHIRAL: [usize; 2] = [6, 7];
+
+/// Cells are given by the planes they are outside of, one bit per face of the icosahedron.
+type Signs = u32;
+
+/// Face plane of the icosahedron: points x with n · x = d.
+struct Plane {
+    n: Vec3<f64>,
+    d: f64,
+}
+
+fn planes() -> Vec<Plane> {
+    let m = Platonic::Icosahedron.mesh::<f64>();
+    m.faces()
+        .map(|f| {
+            let v = m.face_vertices(f);
+            let sum = v.iter().fold(Vec3::zero(), |s, &v| s.add(m.position(v)));
+            let n = sum.normalise();
+            let d = n.dot(m.position(v[0]));
+            Plane { n, d }
+        })
+        .collect()
+}
+
+/// Stellation diagram of a face plane: the lines where the other face planes cut it, and the bounded regions between them.
+pub struct Diagram {
+    /// Other faces, whose planes are not parallel.
+    lines: Vec<usize>,
+    /// Convex polygons, counterclockwise as seen from outside.
+    regions: Vec<Vec<Vec3<f64>>>,
+}
+
+impl Diagram {
+    pub fn new(face: usize) -> Self {
+        let planes = planes();
+        let p = &planes[face];
+        let origin = p.n.scale(&p.d);
+        let m = Platonic::Icosahedron.mesh::<f64>();
+        let corner = m.position(m.face_vertices(face)[0]);
+        let x = corner.sub(&origin).normalise();
+        let y = p.n.cross(&x);
+        let point = |u: f64, v: f64| origin.add(&x.scale(&u)).add(&y.scale(&v));
+
+        // Lines a u + b v = c in the plane's coordinates.
+        let lines: Vec<usize> = (0..planes.len())
+            .filter(|&j| planes[j].n.dot(&p.n).abs() < 1.0 - 1e-9)
+            .collect();
+        let coeffs: Vec<[f64; 3]> = lines
+            .iter()
+            .map(|&j| {
+                let q = &planes[j];
+                [q.n.dot(&x), q.n.dot(&y), q.d - p.d * q.n.dot(&p.n)]
+            })
+            .collect();
+
+        // Crossings, shared between lines where several meet.
+        let mut vertices: Vec<[f64; 2]> = vec![];
+        let mut on_line: Vec<Vec<usize>> = vec![vec![]; lines.len()];
+        for i in 0..lines.len() {
+            for j in 0..i {
+                let ([a, b, c], [d, e, f]) = (coeffs[i], coeffs[j]);
+                let det = a * e - b * d;
+                if det.abs() < 1e-12 {
+                    continue;
+                }
+                let q = [(c * e - b * f) / det, (a * f - c * d) / det];
+                let k = match vertices
+                    .iter()
+                    .position(|v| (v[0] - q[0]).hypot(v[1] - q[1]) < 1e-9)
+                {
+                    Some(k) => k,
+                    None => {
+                        vertices.push(q);
+                        vertices.len() - 1
+                    }
+                };
+                for l in [i, j] {
+                    if !on_line[l].contains(&k) {
+                        on_line[l].push(k);
+                    }
+                }
+            }
+        }
+
+        // Segments between consecutive crossings along each line.
+        let mut neighbours: Vec<Vec<usize>> = vec![vec![]; vertices.len()];
+        for (l, on) in on_line.iter_mut().enumerate() {
+            let [a, b, _] = coeffs[l];
+            on.sort_by(|&i, &j| {
+                let t = |k: usize| -b * vertices[k][0] + a * vertices[k][1];
+                t(i).total_cmp(&t(j))
+            });
+            for w in on.windows(2) {
+                neighbours[w[0]].push(w[1]);
+                neighbours[w[1]].push(w[0]);
+            }
+        }
+        for (v, around) in neighbours.iter_mut().enumerate() {
+            let angle =
+                |u: usize| (vertices[u][1] - vertices[v][1]).atan2(vertices[u][0] - vertices[v][0]);
+            around.sort_by(|&a, &b| angle(a).total_cmp(&angle(b)));
+        }
+
+        // Faces of the planar graph; the outer one winds clockwise, and is dropped.
+        let mut regions = vec![];
+        for face in trace_faces(&neighbours) {
+            let area: f64 = (0..face.len())
+                .map(|i| {
+                    let (a, b) = (vertices[face[i]], vertices[face[(i + 1) % face.len()]]);
+                    a[0] * b[1] - a[1] * b[0]
+                })
+                .sum();
+            if area > 0.0 {
+                regions.push(
+                    face.iter()
+                        .map(|&k| point(vertices[k][0], vertices[k][1]))
+                        .collect(),
+                );
+            }
+        }
+        Self { lines, regions }
+    }
+
+    pub fn lines(&self) -> &[usize] {
+        &self.lines
+    }
+
+    pub fn regions(&self) -> &[Vec<Vec3<f64>>] {
+        &self.regions
+    }
+}
+
+/// Symmetries of the icosahedron, as permutations of its faces: the rotations first, then the reflections.
+fn symmetries() -> Vec<Vec<usize>> {
+    let m = Platonic::Icosahedron.mesh::<f64>();
+    let count = m.half_edges().len();
+    let mut out = vec![];
+    for mirror in [false, true] {
+        for target in 0..count {
+            // Following the next and twin links from half-edge 0 and from its image.
+            let mut map = vec![None; count];
+            map[0] = Some(target);
+            let mut stack = vec![0];
+            while let Some(h) = stack.pop() {
+                let image = map[h].expect("mapped");
+                let (e, i) = (m.half_edge(h), m.half_edge(image));
+                let twin = |h: usize| m.half_edge(h).twin.expect("closed");
+                let steps = [
+                    (e.next, if mirror { i.prev } else { i.next }),
+                    (twin(h), twin(image)),
+                ];
+                for (a, b) in steps {
+                    if map[a].is_none() {
+                        map[a] = Some(b);
+                        stack.push(a);
+                    }
+                }
+            }
+            let mut faces = vec![0; m.face_count()];
+            for h in 0..count {
+                faces[m.half_edge(h).face] = m.half_edge(map[h].expect("mapped")).face;
+            }
+            out.push(faces);
+        }
+    }
+    out
+}
+
+/// The bounded cells that the face planes cut space into, and the regions of the stellation diagrams between them.
+struct Cells {
+    planes: Vec<Plane>,
+    /// Kind of each cell, as an index into `LABELS`.
+    labels: Vec<usize>,
+    index: HashMap<Signs, usize>,
+    walls: Vec<Wall>,
+    /// Cells sharing a wall.
+    neighbours: Vec<Vec<usize>>,
+    /// Whether a cell has a wall with an unbounded one.
+    open: Vec<bool>,
+}
+
+/// Region of a stellation diagram, with the cells on its two sides; `None` where a side is unbounded.
+struct Wall {
+    polygon: Vec<Vec3<f64>>,
+    inner: Option<usize>,
+    outer: Option<usize>,
+}
+
+/// Worked out once, on first use.
+static CELLS: LazyLock<Cells> = LazyLock::new(Cells::new);
+
+impl Cells {
+    fn new() -> Self {
+        let planes = planes();
+
+        // Both sides of each region, taken at its centre.
+        let mut sides = vec![];
+        for face in 0..planes.len() {
+            for polygon in Diagram::new(face).regions {
+                let sum = polygon.iter().fold(Vec3::zero(), |s, v| s.add(v));
+                let centre = sum.scale(&(1.0 / polygon.len() as f64));
+                let inner = signs(&planes, &centre) & !(1 << face);
+                sides.push((polygon, inner, inner | 1 << face));
+            }
+        }
+        let mut cells: Vec<Signs> = sides
+            .iter()
+            .flat_map(|&(_, inner, outer)| [inner, outer])
+            .filter(|&s| is_bounded(&planes, s))
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        cells.sort();
+        let index: HashMap<Signs, usize> = cells.iter().enumerate().map(|(i, &s)| (s, i)).collect();
+        let walls: Vec<Wall> = sides
+            .into_iter()
+            .map(|(polygon, inner, outer)| Wall {
+                polygon,
+                inner: index.get(&inner).copied(),
+                outer: index.get(&outer).copied(),
+            })
+            .collect();
+
+        let mut neighbours = vec![vec![]; cells.len()];
+        let mut open = vec![false; cells.len()];
+        for w in &walls {
+            match (w.inner, w.outer) {
+                (Some(a), Some(b)) => {
+                    neighbours[a].push(b);
+                    neighbours[b].push(a);
+                }
+                (Some(c), None) | (None, Some(c)) => open[c] = true,
+                (None, None) => {}
+            }
+        }
+
+        // Layers, by crossing planes from the centre.
+        let mut layers = vec![None; cells.len()];
+        layers[index[&0]] = Some(0);
+        let mut frontier = vec![index[&0]];
+        for layer in 1.. {
+            let mut next = vec![];
+            for &a in &frontier {
+                for &b in &neighbours[a] {
+                    if layers[b].is_none() {
+                        layers[b] = Some(layer);
+                        next.push(b);
+                    }
+                }
+            }
+            if next.is_empty() {
+                break;
+            }
+            frontier = next;
+        }
+
+        // Kinds of cells are their orbits under the rotations, told apart by layer and size.
+        let rotations = &symmetries()[..60];
+        let mut labels = vec![None; cells.len()];
+        for c in 0..cells.len() {
+            if labels[c].is_some() {
+                continue;
+            }
+            let orbit: BTreeSet<usize> = rotations
+                .iter()
+                .map(|g| {
+                    let s = (0..planes.len())
+                        .filter(|&j| cells[c] >> j & 1 == 1)
+                        .fold(0, |s, j| s | 1 << g[j]);
+                    index[&s]
+                })
+                .collect();
+            let layer = layers[c].expect("reachable");
+            // The first half of f1 to turn up, by the ordering of cells, is called f1.
+            let label = (0..LABELS.len())
+                .find(|&k| {
+                    LAYERS[k] == layer && SIZES[k] == orbit.len() && !labels.contains(&Some(k))
+                })
+                .expect("known kinds of cells");
+            for &o in &orbit {
+                labels[o] = Some(label);
+            }
+        }
+        Self {
+            planes,
+            labels: labels.into_iter().map(|l| l.expect("labelled")).collect(),
+            index,
+            walls,
+            neighbours,
+            open,
+        }
+    }
+
+    fn contains(&self, kinds: u16, cell: Option<usize>) -> bool {
+        cell.is_some_and(|c| kinds >> self.labels[c] & 1 == 1)
+    }
+
+    /// Miller's rules, as used for the 59 icosahedra.
+    ///
+    /// Every face must be on the outside: the cells left out all connect to the unbounded ones.
+    /// The solid can't come apart into two with the same symmetry, where the kinds of cells don't touch;
+    /// the exception is when they are mirror images, which only happens for f1.
+    fn is_valid(&self, kinds: u16) -> bool {
+        if kinds == 0 {
+            return false;
+        }
+        let cells = 0..self.labels.len();
+        let mut outside: Vec<bool> = cells
+            .clone()
+            .map(|c| self.open[c] && !self.contains(kinds, Some(c)))
+            .collect();
+        let mut stack: Vec<usize> = cells.clone().filter(|&c| outside[c]).collect();
+        while let Some(a) = stack.pop() {
+            for &b in &self.neighbours[a] {
+                if !outside[b] && !self.contains(kinds, Some(b)) {
+                    outside[b] = true;
+                    stack.push(b);
+                }
+            }
+        }
+        if cells
+            .clone()
+            .any(|c| !outside[c] && !self.contains(kinds, Some(c)))
+        {
+            return false;
+        }
+
+        let mut touching = [0u16; LABELS.len()];
+        for a in cells {
+            for &b in &self.neighbours[a] {
+                touching[self.labels[a]] |= 1 << self.labels[b];
+            }
+        }
+        let part = |from: u16| {
+            let mut part = from;
+            loop {
+                let next = (0..LABELS.len())
+                    .filter(|&k| part >> k & 1 == 1)
+                    .fold(part, |p, k| p | touching[k] & kinds);
+                if next == part {
+                    return part;
+                }
+                part = next;
+            }
+        };
+        let first = part(1 << kinds.trailing_zeros());
+        first == kinds
+            || (part(kinds & !first) == kinds & !first && mirror(first) == kinds & !first)
+    }
+}
+
+fn signs(planes: &[Plane], p: &Vec3<f64>) -> Signs {
+    (0..planes.len())
+        .filter(|&j| planes[j].n.dot(p) > planes[j].d)
+        .fold(0, |s, j| s | 1 << j)
+}
+
+/// Whether the cell with the given signs is bounded, i.e. no direction leads away within it.
+/// Such a direction could be taken along an edge of the cone of directions, where two of its planes meet.
+fn is_bounded(planes: &[Plane], signs: Signs) -> bool {
+    let normals: Vec<Vec3<f64>> = planes
+        .iter()
+        .enumerate()
+        .map(|(j, p)| {
+            if signs >> j & 1 == 1 {
+                p.n.clone()
+            } else {
+                p.n.neg()
+            }
+        })
+        .collect();
+    let mut directions = vec![];
+    for (j, a) in normals.iter().enumerate() {
+        directions.push(a.clone());
+        for b in &normals[..j] {
+            let d = a.cross(b);
+            if d.norm() > 1e-9 {
+                directions.push(d);
+            }
+        }
+    }
+    !directions
+        .iter()
+        .flat_map(|d| [d.clone(), d.neg()])
+        .any(|d| normals.iter().all(|n| n.dot(&d) >= -1e-9))
+}
+
+/// All kinds of cells in the layers up to the given one.
+fn layers(layer: usize) -> u16 {
+    (0..LABELS.len())
+        .filter(|&k| LAYERS[k] <= layer)
+        .fold(0, |s, k| s | 1 << k)
+}
+
+/// Swaps the two halves of f1.
+fn mirror(kinds: u16) -> u16 {
+    let [a, b] = CHIRAL;
+    let swapped = (kinds >> a & 1) << b | (kinds >> b & 1) << a;
+    kinds & !(1 << a | 1 << b) | swapped
+}
+
+/// Stellation of the icosahedron: a selection of the cells that its face planes cut space into.
+///
+/// Written in Du Val's notation: a capital letter for all layers up to that one, then the kinds of cells
+/// added from further out, see `LABELS`; e.g. C is the compound of five octahedra and Ef1 the compound of ten tetrahedra.
+/// Chiral stellations have only one half of f1, written f1'; the notation doesn't tell the two mirror images apart.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Stellation {
+    /// Kinds of cells, one bit per label.
+    kinds: u16,
+}
+
+impl Stellation {
+    /// Parses the notation as written by `notation`, e.g. `H` or `De1f1g1`.
+    /// Returns `None` unless it is one of the 59; for chiral ones, this is the image listed by `all`, see `mirror` for the other.
+    pub fn parse(s: &str) -> Option<Self> {
+        let mut chars = s.chars().peekable();
+        let mut kinds = 0u16;
+        if let Some(layer) = chars.peek().and_then(|&c| "ABCDEFGH".find(c)) {
+            chars.next();
+            kinds = layers(layer);
+        }
+        while let Some(c) = chars.next() {
+            let mut label = c.to_string();
+            if "efg".contains(c) {
+                label.push(chars.next()?);
+            }
+            if chars.next_if_eq(&'\'').is_some() {
+                label.push('\'');
+            }
+            kinds |= match label.as_str() {
+                "f1" => 1 << CHIRAL[0] | 1 << CHIRAL[1],
+                "f1'" => 1 << CHIRAL[0],
+                _ => 1 << LABELS.iter().position(|&l| l == label)?,
+            };
+        }
+        let stellation = Self { kinds };
+        (CELLS.is_valid(kinds) && stellation.notation() == s).then_some(stellation)
+    }
+
+    /// The 59 stellations, counting mirror images once, in Crennell's order: the main line A to H first,
+    /// then the other reflexible ones, then the chiral ones.
+    pub fn all() -> Vec<Self> {
+        let mut all: Vec<Self> = (1..1 << LABELS.len())
+            .filter(|&k| mirror(k) == k || k >> CHIRAL[0] & 1 == 1)
+            .filter(|&k| CELLS.is_valid(k))
+            .map(|kinds| Self { kinds })
+            .collect();
+        all.sort_by_key(Self::crennell);
+        all
+    }
+
+    /// Number in Crennell's list of the 59, from 1; mirror images share a number.
+    pub fn crennell(&self) -> usize {
+        let notation = self.notation();
+        CRENNELL
+            .iter()
+            .position(|&n| n == notation)
+            .expect("one of the 59")
+            + 1
+    }
+
+    /// The stellation numbered `n` in Crennell's list, see `crennell`.
+    pub fn from_crennell(n: usize) -> Option<Self> {
+        Self::parse(CRENNELL.get(n.checked_sub(1)?)?)
+    }
+
+    /// Whether this is one of A to H, with all cells up to some layer.
+    pub fn is_main(&self) -> bool {
+        (0..8).any(|layer| self.kinds == layers(layer))
+    }
+
+    pub fn is_chiral(&self) -> bool {
+        mirror(self.kinds) != self.kinds
+    }
+
+    /// The mirror image, with the other half of f1.
+    pub fn mirror(&self) -> Self {
+        Self {
+            kinds: mirror(self.kinds),
+        }
+    }
+
+    pub fn notation(&self) -> String {
+        let has = |k: usize| self.kinds >> k & 1 == 1;
+        let full = (0..8)
+            .rev()
+            .find(|&layer| self.kinds & layers(layer) == layers(layer));
+        let mut s = full
+            .map(|l| "ABCDEFGH"[l..=l].to_string())
+            .unwrap_or_default();
+        for k in (0..LABELS.len()).filter(|&k| has(k) && full.is_none_or(|l| LAYERS[k] > l)) {
+            s += match k {
+                k if k == CHIRAL[0] && has(CHIRAL[1]) => "f1",
+                k if k == CHIRAL[1] && has(CHIRAL[0]) => "",
+                k if CHIRAL.contains(&k) => "f1'",
+                k => LABELS[k],
+            };
+        }
+        s
+    }
+
+    /// The common name, for the well-known ones.
+    pub fn name(&self) -> Option<&'static str> {
+        Some(match self.notation().as_str() {
+            "A" => "icosahedron",
+            "C" => "compound of five octahedra",
+            "G" => "great icosahedron",
+            "H" => "final stellation",
+            "Ef1" => "compound of ten tetrahedra",
+            "Ef1'" => "compound of five tetrahedra",
+            _ => return None,
+        })
+    }
+
+    /// Whether a point is inside the solid, for the icosahedron with its vertices on the unit sphere.
+    pub fn contains(&self, p: &Vec3<f64>) -> bool {
+        let cells = &*CELLS;
+        cells.contains(
+            self.kinds,
+            cells.index.get(&signs(&cells.planes, p)).copied(),
+        )
+    }
+
+    /// The visible surface: the regions of the stellation diagrams that have the solid on one side only,
+    /// as polygons facing outwards.
+    pub fn faces(&self) -> Vec<Vec<Vec3<f64>>> {
+        let cells = &*CELLS;
+        cells
+            .walls
+            .iter()
+            .filter_map(|w| {
+                match (
+                    cells.contains(self.kinds, w.inner),
+                    cells.contains(self.kinds, w.outer),
+                ) {
+                    (true, false) => Some(w.polygon.clone()),
+                    (false, true) => Some(w.polygon.iter().rev().cloned().collect()),
+                    _ => None,
+                }
+            })
+            .collect()
+    }
+
+    /// The visible surface as a mesh.
+    /// Faces don't share vertices, since parts of the solid may meet at an edge, where there are four faces.
+    pub fn mesh(&self) -> Mesh<Vec3<f64>> {
+        let faces = self.faces();
+        let mut positions = vec![];
+        let mut indices = vec![];
+        for f in faces {
+            indices.push((positions.len()..positions.len() + f.len()).collect());
+            positions.extend(f);
+        }
+        Mesh::from_faces(positions, &indices).expect("separate faces")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    /// Area times the unit normal.
+    fn area(polygon: &[Vec3<f64>]) -> Vec3<f64> {
+        (0..polygon.len())
+            .fold(Vec3::zero(), |s, i| {
+                s.add(&polygon[i].cross(&polygon[(i + 1) % polygon.len()]))
+            })
+            .scale(&0.5)
+    }
+
+    #[test]
+    fn test_cells() {
+        let d = Diagram::new(0);
+        assert_eq!(d.lines().len(), 18);
+        // The face itself is the region in the middle, then the rest come in threes.
+        assert_eq!(d.regions().len() % 3, 1);
+        let planes = planes();
+        for r in d.regions() {
+            assert!(r.len() >= 3);
+            assert!(area(r).dot(&planes[0].n) > 0.0);
+            for v in r {
+                assert_relative_eq!(planes[0].n.dot(v), planes[0].d, epsilon = 1e-12);
+            }
+        }
+
+        let cells = &*CELLS;
+        assert_eq!(cells.labels.len(), 473);
+        for (k, size) in SIZES.iter().enumerate() {
+            assert_eq!(cells.labels.iter().filter(|&&l| l == k).count(), *size);
+        }
+    }
+
+    #[test]
+    fn test_enumeration() {
+        let all = Stellation::all();
+        assert_eq!(all.len(), 59);
+        assert_eq!(all.iter().filter(|s| s.is_chiral()).count(), 27);
+        let notations: Vec<String> = all.iter().map(|s| s.notation()).collect();
+        assert_eq!(notations[..8], ["A", "B", "C", "D", "E", "F", "G", "H"]);
+        for n in [
+            "e1", "f1", "g1", "e1f1", "e1f1g1", "f1g1", "e2", "f2", "g2", "e2f2", "e2f2g2", "f2g2",
+            "De1", "De1f1", "De1f1g1", "Ef1", "Ef1g1", "Fg1", "De2", "De2f2", "De2f2g2", "Ef2",
+            "Ef2g2", "Fg2",
+        ] {
+            let s = Stellation::parse(n).unwrap();
+            assert!(!s.is_chiral() && !s.is_main());
+            assert_eq!(
+                notations.iter().position(|m| m == n),
+                all.iter().position(|&t| t == s)
+            );
+        }
+        for s in &all {
+            assert_eq!(Stellation::parse(&s.notation()), Some(*s));
+            assert_eq!(s.mirror().notation(), s.notation());
+            assert_eq!(s.mirror() == *s, !s.is_chiral());
+        }
+        // Crennell's numbers.
+        assert_eq!(notations, CRENNELL);
+        for (i, s) in all.iter().enumerate() {
+            assert_eq!(s.crennell(), i + 1);
+            assert_eq!(Stellation::from_crennell(i + 1), Some(*s));
+        }
+        for (n, k) in [
+            ("A", 1),
+            ("C", 3),
+            ("G", 7),
+            ("H", 8),
+            ("Ef1", 22),
+            ("Ef1'", 47),
+        ] {
+            assert_eq!(Stellation::parse(n).unwrap().crennell(), k, "{n}");
+        }
+        assert!(Stellation::from_crennell(0).is_none());
+        assert!(Stellation::from_crennell(60).is_none());
+
+        for n in ["", "h", "Ce1", "e1D", "f1'f1", "f3", "I"] {
+            assert!(Stellation::parse(n).is_none(), "{n}");
+        }
+        assert_eq!(
+            Stellation::parse("G").unwrap().name(),
+            Some("great icosahedron")
+        );
+    }
+
+    #[test]
+    fn test_compounds() {
+        let cells = &*CELLS;
+        let planes = &cells.planes;
+        let inside = |p: &Vec3<f64>, faces: &[usize]| {
+            faces.iter().all(|&j| planes[j].n.dot(p) < planes[j].d)
+        };
+
+        // Tetrahedra, with faces in four planes at equal angles; the rest of their planes make up octahedra.
+        let mut tetrahedra = vec![];
+        for f in 0..20_usize.pow(4) {
+            let t = [0, 1, 2, 3].map(|k| f / 20_usize.pow(k) % 20);
+            let regular = |i: usize, j: usize| {
+                t[i] < t[j] && (planes[t[i]].n.dot(&planes[t[j]].n) + 1.0 / 3.0).abs() < 1e-9
+            };
+            if (0..4).all(|i| (0..4).all(|j| i >= j || regular(i, j))) {
+                tetrahedra.push(t.to_vec());
+            }
+        }
+        assert_eq!(tetrahedra.len(), 10);
+        let octahedra: Vec<Vec<usize>> = tetrahedra
+            .iter()
+            .map(|t| {
+                let opposite = t.iter().map(|&i| {
+                    (0..20)
+                        .find(|&j| planes[i].n.dot(&planes[j].n) < -1.0 + 1e-9)
+                        .unwrap()
+                });
+                t.iter().copied().chain(opposite).collect()
+            })
+            .collect();
+        let rotations = &symmetries()[..60];
+        let five: Vec<Vec<usize>> = tetrahedra
+            .iter()
+            .filter(|t| {
+                rotations.iter().any(|g| {
+                    let mut image: Vec<usize> = tetrahedra[0].iter().map(|&i| g[i]).collect();
+                    image.sort();
+                    let mut t = t.to_vec();
+                    t.sort();
+                    image == t
+                })
+            })
+            .cloned()
+            .collect();
+        assert_eq!(five.len(), 5);
+
+        // Either side of each wall, the solid agrees with the union of the polyhedra.
+        let agrees = |s: &Stellation, solids: &[Vec<usize>]| {
+            cells.walls.iter().all(|w| {
+                let centre = w.polygon.iter().fold(Vec3::zero(), |s, v| s.add(v));
+                let centre = centre.scale(&(1.0 / w.polygon.len() as f64));
+                let n = area(&w.polygon).normalise().scale(&1e-7);
+                [(centre.sub(&n), w.inner), (centre.add(&n), w.outer)]
+                    .iter()
+                    .all(|(p, cell)| {
+                        cells.contains(s.kinds, *cell) == solids.iter().any(|t| inside(p, t))
+                    })
+            })
+        };
+        let c = Stellation::parse("C").unwrap();
+        let ten = Stellation::parse("Ef1").unwrap();
+        let chiral = Stellation::parse("Ef1'").unwrap();
+        assert!(agrees(&c, &octahedra));
+        assert!(agrees(&ten, &tetrahedra));
+        // Which of the two is which depends on the orientation of the icosahedron.
+        assert!(agrees(&chiral, &five) != agrees(&chiral.mirror(), &five));
+        assert_eq!(c.name(), Some("compound of five octahedra"));
+        assert!(ten.contains(&Vec3::zero()));
+        assert!(!ten.contains(&Vec3::new(0.0, 0.0, 10.0)));
+
+        // The surfaces are closed, and the main line grows; the core is the icosahedron.
+        let all = Stellation::all();
+        let volumes: Vec<f64> = all
+            .iter()
+            .map(|s| {
+                let faces = s.faces();
+                let total = faces.iter().fold(Vec3::zero(), |t, f| t.add(&area(f)));
+                assert_relative_eq!(total.norm(), 0.0, epsilon = 1e-9);
+                faces.iter().map(|f| f[0].dot(&area(f)) / 3.0).sum()
+            })
+            .collect();
+        assert!(volumes.iter().all(|&v| v > 0.0));
+        let main = &volumes[..8];
+        let edge = 1.0 / (std::f64::consts::TAU / 5.0).sin();
+        assert_relative_eq!(
+            main[0],
+            5.0 / 12.0 * (3.0 + 5f64.sqrt()) * edge.powi(3),
+            epsilon = 1e-9
+        );
+        assert!(main.windows(2).all(|w| w[0] < w[1]));
+    }
+}
@@ -0,0 +1,42 @@
+use approx::assert_relative_eq;
+use num_traits::ToPrimitive;
+
+use crate::mesh::Mesh;
+use crate::val::Val;
+use crate::vec3::Vec3;
+
+/// Vertices, edges and faces.
+pub fn counts<P>(m: &Mesh<P>) -> (usize, usize, usize) {
+    (m.vertex_count(), m.edge_count(), m.face_count())
+}
+
+/// Faces wind outwards, seen from their centroids.
+pub fn outward(m: &Mesh<Vec3<f64>>) -> bool {
+    m.faces().all(|f| {
+        let v = m.face_vertices(f);
+        let p = |i: usize| m.position(v[i % v.len()]);
+        let (n, c) = (0..v.len()).fold((Vec3::zero(), Vec3::zero()), |(n, c), i| {
+            (n.add(&p(i).cross(p(i + 1))), c.add(p(i)))
+        });
+        n.dot(&c) > 0.0
+    })
+}
+
+pub fn to_f64(v: &Vec3<Val>) -> Vec3<f64> {
+    let [x, y, z] = v.clone().to_array().map(|c| c.to_f64().unwrap());
+    Vec3::new(x, y, z)
+}
+
+pub fn assert_close(a: &Vec3<f64>, b: &Vec3<f64>) {
+    for (x, y) in a.clone().to_array().iter().zip(b.clone().to_array()) {
+        assert_relative_eq!(*x, y, epsilon = 1e-12);
+    }
+}
+
+/// Exact positions agree with the numeric ones.
+pub fn assert_agree(exact: &[Vec3<Val>], numeric: &[Vec3<f64>]) {
+    assert_eq!(exact.len(), numeric.len());
+    for (p, q) in exact.iter().zip(numeric) {
+        assert_close(&to_f64(p), q);
+    }
+}
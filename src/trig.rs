@@ -0,0 +1,400 @@
+use std::f64::consts::PI;
+
+use num_rational::BigRational;
+use num_traits::{One, ToPrimitive};
+
+use crate::val::{Angle, Val};
+
+impl Val {
+    /// Rewrites the value bottom-up using trigonometric identities.
+    ///
+    /// Identities that expand an expression (angle sums) are only kept if the result is cheaper.
+    /// Those that only hold on part of the domain (e.g. `asin(sin(a)) = a`) are only applied when
+    /// the argument can be evaluated and is known to be in range.
+    pub fn simplify_trig(&self) -> Self {
+        if let Some(r) = self.to_rational() {
+            return r.into();
+        }
+        match self {
+            Self::Int(_) | Self::Float(_) | Self::Param(_) => self.clone(),
+            Self::Sum(a, b) => sin_cos_sq(&a.simplify_trig(), &b.simplify_trig()),
+            Self::Dif(a, b) => a.simplify_trig().sub(&b.simplify_trig()),
+            Self::Prd(a, b) => a.simplify_trig().mul(&b.simplify_trig()),
+            Self::Rat(a, b) => a.simplify_trig().div(&b.simplify_trig()),
+            Self::Pow(a, b) => a.simplify_trig().pow(&b.simplify_trig()),
+            Self::Sqrt(a) => a.simplify_trig().sqrt(),
+            Self::Sin(a) => sin(&a.simplify_trig()),
+            Self::Cos(a) => cos(&a.simplify_trig()),
+            Self::Tan(a) => tan(&a.simplify_trig()),
+            Self::Exp(a) => a.simplify_trig().exp(),
+            Self::Ln(a) => a.simplify_trig().ln(),
+            Self::Abs(a) => a.simplify_trig().abs(),
+            Self::Sign(a) => a.simplify_trig().sign(),
+            Self::Min(a, b) => a.simplify_trig().min(&b.simplify_trig()),
+            Self::Max(a, b) => a.simplify_trig().max(&b.simplify_trig()),
+            Self::If(c, a, b) => Self::piecewise(
+                &c.map(&|v| v.simplify_trig()),
+                &a.simplify_trig(),
+                &b.simplify_trig(),
+            ),
+        }
+    }
+}
+
+impl Angle {
+    /// Rewrites the angle bottom-up using inverse trigonometric identities.
+    pub fn simplify_trig(&self) -> Self {
+        match self {
+            Self::Pi(a) => Self::Pi(Box::new(a.simplify_trig())),
+            Self::Sum(a, b) => collect(&a.simplify_trig(), &b.simplify_trig(), false),
+            Self::Dif(a, b) => collect(&a.simplify_trig(), &b.simplify_trig(), true),
+            Self::Prd(a, b) => a.simplify_trig().mul(&b.simplify_trig()),
+            Self::Rat(a, b) => a.simplify_trig().div(&b.simplify_trig()),
+            Self::ASin(a) => match a.simplify_trig() {
+                // asin(sin(a)) = a, for a in [-pi/2, pi/2]
+                Val::Sin(x) if within(&x, -0.5, 0.5) => x,
+                x => Self::ASin(Box::new(x)),
+            },
+            Self::ACos(a) => match a.simplify_trig() {
+                // acos(cos(a)) = a, for a in [0, pi]
+                Val::Cos(x) if within(&x, 0.0, 1.0) => x,
+                x => Self::ACos(Box::new(x)),
+            },
+            Self::ATan(a) => match a.simplify_trig() {
+                // atan(tan(a)) = a, for a in (-pi/2, pi/2)
+                Val::Tan(x) if within(&x, -0.5, 0.5) => x,
+                x => Self::ATan(Box::new(x)),
+            },
+            Self::ATan2(a, b) => a.simplify_trig().atan2(&b.simplify_trig()),
+        }
+    }
+}
+
+/// a x + b x = (a + b) x, and likewise for differences, if that is cheaper.
+/// Latitudes like alpha - alpha t / 2 come out this way from chains of Norm rotations.
+fn collect(a: &Angle, b: &Angle, dif: bool) -> Angle {
+    let orig = if dif { a.sub(b) } else { a.add(b) };
+    // a + b (-1) = a - b
+    let (b, dif) = match b {
+        Angle::Prd(x, y) if y.to_i64() == Some(-1) => (x.as_ref(), !dif),
+        _ => (b, dif),
+    };
+    let ((x, p), (y, q)) = (factor(a), factor(b));
+    if x != y {
+        return orig;
+    }
+    let rewritten = x.mul(&if dif { p.dif(&q) } else { p.add(&q) });
+    if rewritten.cost() < orig.cost() {
+        rewritten
+    } else {
+        orig
+    }
+}
+
+/// Splits off the factor of an angle, e.g. (x, t / 2) for x t / 2.
+fn factor(a: &Angle) -> (Angle, Val) {
+    match a {
+        Angle::Prd(x, y) => {
+            let (x, c) = factor(x);
+            (x, c.mul(y))
+        }
+        Angle::Rat(x, y) => {
+            let (x, c) = factor(x);
+            (x, c.div(y))
+        }
+        _ => (a.clone(), 1.into()),
+    }
+}
+
+/// Checks if the angle evaluates to somewhere in [lo*pi, hi*pi].
+fn within(a: &Angle, lo: f64, hi: f64) -> bool {
+    a.to_f64().is_some_and(|x| x >= lo * PI && x <= hi * PI)
+}
+
+/// sin^2(a) + cos^2(a) = 1
+fn sin_cos_sq(a: &Val, b: &Val) -> Val {
+    match (sq_arg(a), sq_arg(b)) {
+        (Some(Val::Sin(x)), Some(Val::Cos(y))) | (Some(Val::Cos(x)), Some(Val::Sin(y)))
+            if x == y =>
+        {
+            1.into()
+        }
+        _ => a.add(b),
+    }
+}
+
+/// If the value is a square, returns its base.
+fn sq_arg(a: &Val) -> Option<Val> {
+    match a {
+        Val::Pow(x, e) if e.to_i64() == Some(2) => Some(*x.clone()),
+        Val::Prd(x, y) if x == y => Some(*x.clone()),
+        _ => None,
+    }
+}
+
+fn sin(a: &Angle) -> Val {
+    match a {
+        Angle::Pi(x) => match x.to_rational() {
+            Some(r) => cheaper(a.sin(), cos_pi(&(half() - r)).unwrap_or_else(|| a.sin())),
+            None => a.sin(),
+        },
+        // sin(asin(x)) = x
+        Angle::ASin(x) => *x.clone(),
+        // sin(acos(x)) = sqrt(1 - x^2)
+        Angle::ACos(x) => one_minus_sq(x),
+        // sin(atan(x)) = x / sqrt(1 + x^2)
+        Angle::ATan(x) => x.div(&sq(x).iadd(1).sqrt()),
+        // sin(atan2(y, x)) = y / sqrt(x^2 + y^2)
+        Angle::ATan2(y, x) => y.div(&sq(x).add(&sq(y)).sqrt()),
+        // sin(-a) = -sin(a)
+        Angle::Prd(x, y) if y.to_i64() == Some(-1) => sin(x).neg(),
+        // sin(a + b) = sin(a) cos(b) + cos(a) sin(b)
+        Angle::Sum(x, y) => cheaper(a.sin(), sin(x).mul(&cos(y)).add(&cos(x).mul(&sin(y)))),
+        // sin(a - b) = sin(a) cos(b) - cos(a) sin(b)
+        Angle::Dif(x, y) => cheaper(a.sin(), sin(x).mul(&cos(y)).sub(&cos(x).mul(&sin(y)))),
+        _ => a.sin(),
+    }
+}
+
+fn cos(a: &Angle) -> Val {
+    match a {
+        Angle::Pi(x) => match x.to_rational() {
+            Some(r) => cheaper(a.cos(), cos_pi(&r).unwrap_or_else(|| a.cos())),
+            None => a.cos(),
+        },
+        // cos(asin(x)) = sqrt(1 - x^2)
+        Angle::ASin(x) => one_minus_sq(x),
+        // cos(acos(x)) = x
+        Angle::ACos(x) => *x.clone(),
+        // cos(atan(x)) = 1 / sqrt(1 + x^2)
+        Angle::ATan(x) => sq(x).iadd(1).sqrt().rec(),
+        // cos(atan2(y, x)) = x / sqrt(x^2 + y^2)
+        Angle::ATan2(y, x) => x.div(&sq(x).add(&sq(y)).sqrt()),
+        // cos(-a) = cos(a)
+        Angle::Prd(x, y) if y.to_i64() == Some(-1) => cos(x),
+        // cos(a + b) = cos(a) cos(b) - sin(a) sin(b)
+        Angle::Sum(x, y) => cheaper(a.cos(), cos(x).mul(&cos(y)).sub(&sin(x).mul(&sin(y)))),
+        // cos(a - b) = cos(a) cos(b) + sin(a) sin(b)
+        Angle::Dif(x, y) => cheaper(a.cos(), cos(x).mul(&cos(y)).add(&sin(x).mul(&sin(y)))),
+        _ => a.cos(),
+    }
+}
+
+fn tan(a: &Angle) -> Val {
+    match a {
+        // tan(asin(x)) = x / sqrt(1 - x^2)
+        Angle::ASin(x) => x.div(&one_minus_sq(x)),
+        // tan(acos(x)) = sqrt(1 - x^2) / x
+        Angle::ACos(x) => one_minus_sq(x).div(x),
+        // tan(atan(x)) = x
+        Angle::ATan(x) => *x.clone(),
+        // tan(atan2(y, x)) = y / x
+        Angle::ATan2(y, x) => y.div(x),
+        // tan(-a) = -tan(a)
+        Angle::Prd(x, y) if y.to_i64() == Some(-1) => tan(x).neg(),
+        // tan(a) = sin(a) / cos(a), if that simplifies to something cheaper.
+        _ => match cos(a) {
+            c if c.is_zero() => a.tan(),
+            c => cheaper(a.tan(), sin(a).div(&c)),
+        },
+    }
+}
+
+/// sqrt(1 - x^2), folded for rational x.
+fn one_minus_sq(x: &Val) -> Val {
+    let d = Val::from(1).sub(&sq(x));
+    d.to_rational().map_or(d, Val::from).sqrt()
+}
+
+/// x^2, pushed down through products, ratios and square roots.
+fn sq(x: &Val) -> Val {
+    match x {
+        Val::Int(_) | Val::Float(_) => x.mul(x),
+        Val::Prd(a, b) => sq(a).mul(&sq(b)),
+        Val::Rat(a, b) => sq(a).div(&sq(b)),
+        Val::Sqrt(a) => *a.clone(),
+        _ => x.ipow(2),
+    }
+}
+
+/// Picks the rewritten value only if it has fewer nodes.
+/// Exact values of e.g. cos(2pi/5) are not always shorter than the original.
+fn cheaper(orig: Val, rewritten: Val) -> Val {
+    if rewritten.cost() < orig.cost() {
+        rewritten
+    } else {
+        orig
+    }
+}
+
+fn half() -> BigRational {
+    BigRational::new(1.into(), 2.into())
+}
+
+/// Exact value of cos(r*pi), where known.
+fn cos_pi(r: &BigRational) -> Option<Val> {
+    // Reduce to [0, 2), then use cos(2pi - a) = cos(a) and cos(pi - a) = -cos(a).
+    let two = BigRational::from_integer(2.into());
+    let r = r - (r / &two).floor() * &two;
+    let r = if r > BigRational::one() { two - r } else { r };
+    if r > half() {
+        return cos_pi_table(&(BigRational::one() - r)).map(|v| v.neg());
+    }
+    cos_pi_table(&r)
+}
+
+/// Exact value of cos(r*pi) for r in [0, 1/2].
+fn cos_pi_table(r: &BigRational) -> Option<Val> {
+    let sqrt5 = || Val::from(5).sqrt();
+    Some(match (r.numer().to_i64()?, r.denom().to_i64()?) {
+        (0, _) => 1.into(),
+        (1, 2) => 0.into(),
+        (1, 3) => Val::from(1).idiv(2),
+        (1, 4) => Val::from(2).sqrt().idiv(2),
+        (1, 6) => Val::from(3).sqrt().idiv(2),
+        (1, 5) => sqrt5().iadd(1).idiv(4),
+        (2, 5) => sqrt5().isub(1).idiv(4),
+        (1, 10) => sqrt5().iadd(5).idiv(8).sqrt(),
+        (3, 10) => Val::from(5).sub(&sqrt5()).idiv(8).sqrt(),
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{alpha, beta, Angle, Norm, Val};
+    use approx::assert_relative_eq;
+    use num_traits::ToPrimitive;
+
+    fn assert_same(v: &Val) -> Val {
+        let s = v.simplify_trig();
+        assert_relative_eq!(s.to_f64().unwrap(), v.to_f64().unwrap(), epsilon = 1e-12);
+        s
+    }
+
+    #[test]
+    fn test_inverse() {
+        let x = Val::from(1).idiv(3);
+        // Rational arguments fold.
+        assert_eq!(x.acos().sin().simplify_trig(), Val::from(8).idiv(9).sqrt());
+        assert_eq!(x.acos().sin().simplify_trig().cost(), one_minus_sq_cost(&x));
+        assert_eq!(x.asin().cos().simplify_trig().cost(), one_minus_sq_cost(&x));
+        assert_eq!(x.atan().tan().simplify_trig(), x);
+        assert_same(&x.atan().sin());
+        assert_same(&x.atan().cos());
+        assert_same(&x.acos().tan());
+        assert_same(&x.asin().tan());
+        for (y, x) in [(1, 2), (-1, 2), (1, -2), (-1, -2)] {
+            let a = Val::from(y).atan2(&Val::from(x).idiv(3));
+            assert_same(&a.sin());
+            assert_same(&a.cos());
+            assert_same(&a.tan());
+        }
+    }
+
+    fn one_minus_sq_cost(x: &Val) -> usize {
+        super::one_minus_sq(x).cost()
+    }
+
+    #[test]
+    fn test_exact_values() {
+        for i in -12..=12 {
+            for d in [1, 2, 3, 4, 5, 6, 10] {
+                let a = Angle::Pi(Box::new(Val::from(i).idiv(d)));
+                assert_same(&a.sin());
+                assert_same(&a.cos());
+            }
+            // Rational values are always cheaper than the trig fns.
+            for d in [1, 2, 3] {
+                let a = Angle::Pi(Box::new(Val::from(i).idiv(d)));
+                assert!(a.cos().simplify_trig().to_rational().is_some());
+            }
+        }
+    }
+
+    #[test]
+    fn test_exact_sin() {
+        let a = Angle::part(12).imul(5);
+        assert_eq!(a.sin().simplify_trig(), Val::from(1).idiv(2));
+        assert_eq!(a.neg().sin().simplify_trig(), Val::from(-1).idiv(2));
+    }
+
+    #[test]
+    fn test_collect() {
+        let t = Val::param(1);
+        let a = alpha().sub(&alpha().mul(&t).idiv(2));
+        assert_eq!(
+            a.simplify_trig(),
+            alpha().mul(&Val::from(1).dif(&t.idiv(2)))
+        );
+        let a = alpha().add(&alpha().mul(&t));
+        assert_eq!(a.simplify_trig(), alpha().mul(&Val::from(1).add(&t)));
+        // Different angles are left alone.
+        let a = alpha().add(&beta().mul(&t));
+        assert_eq!(a.simplify_trig(), a);
+    }
+
+    #[test]
+    fn test_pythagorean() {
+        let a = alpha().add(&Angle::part(7).mul(&Val::param(1)));
+        let v = a.sin().ipow(2).add(&a.cos().ipow(2));
+        assert_eq!(v.simplify_trig(), Val::from(1));
+    }
+
+    #[test]
+    fn test_supplement() {
+        let a = alpha().mul(&Val::param(1));
+        let v = Angle::part(2).sub(&a).cos();
+        let s = v.simplify_trig();
+        assert_eq!(s.cost(), a.cos().neg().cost());
+        assert_relative_eq!(
+            s.subs(1, &Val::from(1).idiv(3)).to_f64().unwrap(),
+            v.subs(1, &Val::from(1).idiv(3)).to_f64().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_domain() {
+        // asin(sin(2a)) != 2a, since 2a > pi/2.
+        let a = alpha().imul(2);
+        let v = Angle::ASin(Box::new(Val::Sin(a.clone())));
+        assert!(matches!(v.simplify_trig(), Angle::ASin(_)));
+
+        let a = alpha().idiv(2);
+        let v = Angle::ASin(Box::new(Val::Sin(a.clone())));
+        assert_eq!(v.simplify_trig(), a);
+    }
+
+    #[test]
+    fn test_geom_shrinks() {
+        let top = Norm::zero();
+        let fifth = Angle::part(5);
+        let tenth = Angle::part(10);
+        let by = alpha().mul(&Val::param(1)).idiv(2);
+
+        // Vertices used in web::geom.
+        let points: Vec<Norm> = (0..5_i64)
+            .flat_map(|i| {
+                [
+                    top.south(&beta()).east(&tenth).east(&fifth.imul(i)),
+                    top.south(&by).east(&fifth.imul(i)),
+                    top.south(&alpha()).north(&by).east(&fifth.imul(i)),
+                ]
+            })
+            .collect();
+
+        let (mut before, mut after) = (0, 0);
+        for v in points.iter().flat_map(|p| [p.x(), p.y(), p.z()]) {
+            let s = v.simplify_trig();
+            let t = Val::from(1).idiv(2);
+            assert_relative_eq!(
+                s.subs(1, &t).to_f64().unwrap(),
+                v.subs(1, &t).to_f64().unwrap(),
+                epsilon = 1e-12
+            );
+            before += v.cost();
+            after += s.cost();
+        }
+        assert!(after * 10 < before * 9, "{} -> {}", before, after);
+    }
+}
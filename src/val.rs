@@ -5,11 +5,14 @@ use std::f64::consts::PI;
 use std::fmt;
 
 use num_bigint::BigInt as Int;
-use num_traits::{One, ToPrimitive, Zero};
+use num_rational::BigRational;
+use num_traits::{One, Signed, ToPrimitive, Zero};
 
-#[derive(Clone)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum Val {
     Int(Int),
+    // Approximate literal:
+    Float(f64),
     // Numeric ops:
     Sum(Box<Self>, Box<Self>),
     Dif(Box<Self>, Box<Self>),
@@ -21,11 +24,20 @@ pub enum Val {
     Sin(Angle),
     Cos(Angle),
     Tan(Angle),
+    // Other fns:
+    Exp(Box<Self>),
+    Ln(Box<Self>),
+    Abs(Box<Self>),
+    Sign(Box<Self>),
+    Min(Box<Self>, Box<Self>),
+    Max(Box<Self>, Box<Self>),
+    // Piecewise:
+    If(Box<Cond>, Box<Self>, Box<Self>),
     // Parameter:
     Param(usize),
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum Angle {
     Pi(Box<Val>),
     // Numeric ops:
@@ -37,6 +49,18 @@ pub enum Angle {
     ASin(Box<Val>),
     ACos(Box<Val>),
     ATan(Box<Val>),
+    ATan2(Box<Val>, Box<Val>),
+}
+
+/// Condition of a piecewise value.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Cond {
+    Lt(Box<Val>, Box<Val>),
+    Le(Box<Val>, Box<Val>),
+    Eq(Box<Val>, Box<Val>),
+    And(Box<Self>, Box<Self>),
+    Or(Box<Self>, Box<Self>),
+    Not(Box<Self>),
 }
 
 impl Val {
@@ -54,6 +78,8 @@ impl Val {
             match (&self, &a) {
                 // Otherwise try to push down the operation.
                 (Self::Int(x), Self::Int(y)) => Self::Int(x + y),
+                (Self::Float(_), Self::Float(_) | Self::Int(_))
+                | (Self::Int(_), Self::Float(_)) => Self::Float(self.float() + a.float()),
                 // If that doesn't work, box the sum into a new enum.
                 _ => Self::Sum(Box::new(self.clone()), Box::new(a.clone())),
             }
@@ -64,10 +90,20 @@ impl Val {
         self.add(&a.neg())
     }
 
+    /// Like `sub`, but keeps a symbolic difference as such instead of negating it.
+    pub(crate) fn dif(&self, a: &Val) -> Self {
+        match (&self, &a) {
+            (Self::Int(_) | Self::Float(_), Self::Int(_) | Self::Float(_)) => self.sub(a),
+            _ if a.is_zero() => self.clone(),
+            _ => Self::Dif(Box::new(self.clone()), Box::new(a.clone())),
+        }
+    }
+
     pub fn neg(&self) -> Self {
         match self {
             // Try to push down the operation.
             Self::Int(x) => Self::Int(-x),
+            Self::Float(x) => Self::Float(-x),
             Self::Dif(x, y) => Self::Dif(y.clone(), x.clone()),
             Self::Prd(x, y) => Self::Prd(Box::new(x.neg()), y.clone()),
             Self::Rat(x, y) => Self::Rat(Box::new(x.neg()), y.clone()),
@@ -88,6 +124,8 @@ impl Val {
             match (&self, &a) {
                 // Otherwise try to push down the operation.
                 (Self::Int(x), Self::Int(y)) => Self::Int(x * y),
+                (Self::Float(_), Self::Float(_) | Self::Int(_))
+                | (Self::Int(_), Self::Float(_)) => Self::Float(self.float() * a.float()),
                 // If that doesn't work, bodx the product int a new enum.
                 _ => Self::Prd(Box::new(self.clone()), Box::new(a.clone())),
             }
@@ -101,6 +139,8 @@ impl Val {
             0.into()
         } else if a.is_one() {
             self.clone()
+        } else if self.is_float_lit(a) {
+            Self::Float(self.float() / a.float())
         } else {
             Self::Rat(Box::new(self.clone()), Box::new(a.clone()))
         }
@@ -113,6 +153,7 @@ impl Val {
             1.into()
         } else {
             match &self {
+                Self::Float(x) => Self::Float(1.0 / x),
                 Self::Rat(a, b) => Self::Rat(b.clone(), a.clone()),
                 _ => Self::Rat(Box::from(Val::from(1)), Box::from(self.clone())),
             }
@@ -126,6 +167,8 @@ impl Val {
             0.into()
         } else if a.is_zero() {
             1.into()
+        } else if self.is_float_lit(a) {
+            Self::Float(self.float().powf(a.float()))
         } else {
             Self::Pow(Box::new(self.clone()), Box::new(a.clone()))
         }
@@ -136,6 +179,10 @@ impl Val {
             0.into()
         } else if self.is_one() {
             1.into()
+        } else if let Some(x) = self.to_rational().as_ref().and_then(sqrt_exact) {
+            x.into()
+        } else if let Self::Float(x) = self {
+            Self::Float(x.sqrt())
         } else {
             Self::Sqrt(Box::new(self.clone()))
         }
@@ -146,6 +193,12 @@ impl Val {
     }
 
     pub fn asin(&self) -> Angle {
+        if let Self::Float(x) = self {
+            return Angle::float(x.asin());
+        }
+        if let Some(a) = self.to_rational().as_ref().and_then(asin_pi) {
+            return Angle::Pi(Box::new(a.into()));
+        }
         match self {
             Self::Sin(x) => x.clone(),
             _ => Angle::ASin(Box::new(self.clone())),
@@ -153,6 +206,12 @@ impl Val {
     }
 
     pub fn acos(&self) -> Angle {
+        if let Self::Float(x) = self {
+            return Angle::float(x.acos());
+        }
+        if let Some(a) = self.to_rational().as_ref().and_then(asin_pi) {
+            return Angle::Pi(Box::new((BigRational::new(1.into(), 2.into()) - a).into()));
+        }
         match self {
             Self::Cos(x) => x.clone(),
             _ => Angle::ACos(Box::new(self.clone())),
@@ -161,14 +220,120 @@ impl Val {
 
     pub fn atan(&self) -> Angle {
         match self {
+            Self::Float(x) => Angle::float(x.atan()),
             Self::Tan(x) => x.clone(),
             _ => Angle::ATan(Box::new(self.clone())),
         }
     }
 
+    /// Four-quadrant arctangent of `self / x`, like `f64::atan2`.
+    pub fn atan2(&self, x: &Val) -> Angle {
+        if self.is_float_lit(x) {
+            return Angle::float(self.float().atan2(x.float()));
+        }
+        // Signs are exact for rationals, otherwise certified using intervals.
+        let sign = |v: &Val| match v.to_rational() {
+            Some(r) => r.signum().to_i64(),
+            None => v.to_interval().and_then(|i| {
+                if i.lo() > 0.0 {
+                    Some(1)
+                } else if i.hi() < 0.0 {
+                    Some(-1)
+                } else {
+                    None
+                }
+            }),
+        };
+        match (sign(self), sign(x)) {
+            (Some(0), Some(1)) => return Angle::zero(),
+            (Some(0), Some(-1)) => return Angle::turn().idiv(2),
+            (Some(y), Some(0)) if y != 0 => return Angle::turn().idiv(4).imul(y),
+            // On the diagonal.
+            (Some(1), Some(1)) if self == x => return Angle::turn().idiv(8),
+            (Some(-1), Some(-1)) if self == x => return Angle::turn().idiv(8).imul(-3),
+            _ => {}
+        }
+        match (self.to_rational(), x.to_rational()) {
+            (Some(y), Some(x)) if y.abs() == x.abs() && !x.is_zero() => Angle::turn()
+                .idiv(8)
+                .imul(if x.is_positive() { 1 } else { 3 })
+                .imul(y.signum().to_i64().unwrap_or(1)),
+            _ => Angle::ATan2(Box::new(self.clone()), Box::new(x.clone())),
+        }
+    }
+
+    pub fn exp(&self) -> Self {
+        match self {
+            _ if self.is_zero() => 1.into(),
+            Self::Ln(x) => *x.clone(),
+            _ => Self::Exp(Box::new(self.clone())),
+        }
+    }
+
+    /// Natural logarithm.
+    pub fn ln(&self) -> Self {
+        match self {
+            _ if self.is_one() => 0.into(),
+            Self::Exp(x) => *x.clone(),
+            _ => Self::Ln(Box::new(self.clone())),
+        }
+    }
+
+    pub fn abs(&self) -> Self {
+        match self.to_rational() {
+            Some(x) => x.abs().into(),
+            None => match self {
+                Self::Abs(_) | Self::Sqrt(_) | Self::Exp(_) => self.clone(),
+                _ => Self::Abs(Box::new(self.clone())),
+            },
+        }
+    }
+
+    /// Sign of the value: -1, 0 or 1.
+    pub fn sign(&self) -> Self {
+        match self.to_rational() {
+            Some(x) => x.signum().into(),
+            None => match self {
+                Self::Exp(_) => 1.into(),
+                _ => Self::Sign(Box::new(self.clone())),
+            },
+        }
+    }
+
+    pub fn min(&self, a: &Val) -> Self {
+        match (self.to_rational(), a.to_rational()) {
+            (Some(x), Some(y)) => x.min(y).into(),
+            _ if self == a => self.clone(),
+            _ => Self::Min(Box::new(self.clone()), Box::new(a.clone())),
+        }
+    }
+
+    pub fn max(&self, a: &Val) -> Self {
+        match (self.to_rational(), a.to_rational()) {
+            (Some(x), Some(y)) => x.max(y).into(),
+            _ if self == a => self.clone(),
+            _ => Self::Max(Box::new(self.clone()), Box::new(a.clone())),
+        }
+    }
+
+    /// Piecewise value: `then` where `cond` holds, `other` elsewhere.
+    pub fn piecewise(cond: &Cond, then: &Val, other: &Val) -> Self {
+        match cond.to_exact() {
+            Some(true) => then.clone(),
+            Some(false) => other.clone(),
+            None if then == other => then.clone(),
+            None => Self::If(
+                Box::new(cond.clone()),
+                Box::new(then.clone()),
+                Box::new(other.clone()),
+            ),
+        }
+    }
+
     pub fn is_zero(&self) -> bool {
         match self {
             Self::Int(x) => x.is_zero(),
+            Self::Float(x) => *x == 0.0,
             _ => false,
         }
     }
@@ -176,10 +341,159 @@ impl Val {
     fn is_one(&self) -> bool {
         match self {
             Self::Int(x) => x.is_one(),
+            Self::Float(x) => *x == 1.0,
             _ => false,
         }
     }
 
+    /// Exact rational value, if this is built from integers with arithmetic only.
+    pub fn to_rational(&self) -> Option<BigRational> {
+        match self {
+            Self::Int(a) => Some(a.clone().into()),
+            Self::Sum(a, b) => a.to_rational().and_then(|x| b.to_rational().map(|y| x + y)),
+            Self::Dif(a, b) => a.to_rational().and_then(|x| b.to_rational().map(|y| x - y)),
+            Self::Prd(a, b) => a.to_rational().and_then(|x| b.to_rational().map(|y| x * y)),
+            Self::Rat(a, b) => a
+                .to_rational()
+                .and_then(|x| b.to_rational().filter(|y| !y.is_zero()).map(|y| x / y)),
+            Self::Pow(a, b) => a.to_rational().and_then(|x| {
+                b.to_i64()
+                    .and_then(|y| y.try_into().ok())
+                    .filter(|y: &i32| !x.is_zero() || *y > 0)
+                    .map(|y| x.pow(y))
+            }),
+            Self::Sqrt(a) => a.to_rational().as_ref().and_then(sqrt_exact),
+            Self::Abs(a) => a.to_rational().map(|x| x.abs()),
+            Self::Sign(a) => a.to_rational().map(|x| x.signum()),
+            Self::Min(a, b) => a
+                .to_rational()
+                .and_then(|x| b.to_rational().map(|y| x.min(y))),
+            Self::Max(a, b) => a
+                .to_rational()
+                .and_then(|x| b.to_rational().map(|y| x.max(y))),
+            Self::If(c, a, b) => match c.to_exact()? {
+                true => a.to_rational(),
+                false => b.to_rational(),
+            },
+            _ => None,
+        }
+    }
+
+    /// Value of a numeric literal, used when folding floats.
+    fn float(&self) -> f64 {
+        self.to_f64().unwrap_or(f64::NAN)
+    }
+
+    /// Checks if both are literals, at least one of them approximate, so the result would be too.
+    fn is_float_lit(&self, a: &Val) -> bool {
+        matches!(
+            (self, a),
+            (Self::Float(_), Self::Float(_) | Self::Int(_)) | (Self::Int(_), Self::Float(_))
+        )
+    }
+
+    /// Checks if any parameter appears in this value.
+    pub fn has_param(&self) -> bool {
+        match self {
+            Self::Int(_) | Self::Float(_) => false,
+            Self::Sum(a, b)
+            | Self::Dif(a, b)
+            | Self::Prd(a, b)
+            | Self::Rat(a, b)
+            | Self::Pow(a, b) => a.has_param() || b.has_param(),
+            Self::Sqrt(a) | Self::Exp(a) | Self::Ln(a) | Self::Abs(a) | Self::Sign(a) => {
+                a.has_param()
+            }
+            Self::Sin(a) | Self::Cos(a) | Self::Tan(a) => a.has_param(),
+            Self::Min(a, b) | Self::Max(a, b) => a.has_param() || b.has_param(),
+            Self::If(c, a, b) => c.has_param() || a.has_param() || b.has_param(),
+            Self::Param(_) => true,
+        }
+    }
+
+    /// Number of nodes in the expression tree.
+    pub fn cost(&self) -> usize {
+        match self {
+            Self::Int(_) | Self::Float(_) | Self::Param(_) => 1,
+            Self::Sum(a, b)
+            | Self::Dif(a, b)
+            | Self::Prd(a, b)
+            | Self::Rat(a, b)
+            | Self::Pow(a, b) => 1 + a.cost() + b.cost(),
+            Self::Sqrt(a) | Self::Exp(a) | Self::Ln(a) | Self::Abs(a) | Self::Sign(a) => {
+                1 + a.cost()
+            }
+            Self::Sin(a) | Self::Cos(a) | Self::Tan(a) => 1 + a.cost(),
+            Self::Min(a, b) | Self::Max(a, b) => 1 + a.cost() + b.cost(),
+            Self::If(c, a, b) => 1 + c.cost() + a.cost() + b.cost(),
+        }
+    }
+
+    /// Replaces parameter `t` with the value `v`, e.g. to freeze a solved parameter.
+    pub fn subs(&self, t: usize, v: &Val) -> Self {
+        match self {
+            Self::Int(_) | Self::Float(_) => self.clone(),
+            Self::Sum(a, b) => a.subs(t, v).add(&b.subs(t, v)),
+            Self::Dif(a, b) => a.subs(t, v).dif(&b.subs(t, v)),
+            Self::Prd(a, b) => a.subs(t, v).mul(&b.subs(t, v)),
+            Self::Rat(a, b) => a.subs(t, v).div(&b.subs(t, v)),
+            Self::Pow(a, b) => a.subs(t, v).pow(&b.subs(t, v)),
+            Self::Sqrt(a) => a.subs(t, v).sqrt(),
+            Self::Sin(a) => a.subs(t, v).sin(),
+            Self::Cos(a) => a.subs(t, v).cos(),
+            Self::Tan(a) => a.subs(t, v).tan(),
+            Self::Exp(a) => a.subs(t, v).exp(),
+            Self::Ln(a) => a.subs(t, v).ln(),
+            Self::Abs(a) => a.subs(t, v).abs(),
+            Self::Sign(a) => a.subs(t, v).sign(),
+            Self::Min(a, b) => a.subs(t, v).min(&b.subs(t, v)),
+            Self::Max(a, b) => a.subs(t, v).max(&b.subs(t, v)),
+            Self::If(c, a, b) => Self::piecewise(&c.subs(t, v), &a.subs(t, v), &b.subs(t, v)),
+            Self::Param(u) => {
+                if *u == t {
+                    v.clone()
+                } else {
+                    self.clone()
+                }
+            }
+        }
+    }
+
+    /// Collapses parameter-free subtrees costing more than `cost` nodes into float literals.
+    /// Parameter-dependent parts are kept symbolic, with their children approximated.
+    pub fn approximate(&self, cost: usize) -> Self {
+        if !self.has_param() {
+            return if self.cost() > cost {
+                Self::Float(self.float())
+            } else {
+                self.clone()
+            };
+        }
+        match self {
+            Self::Sum(a, b) => a.approximate(cost).add(&b.approximate(cost)),
+            Self::Dif(a, b) => a.approximate(cost).dif(&b.approximate(cost)),
+            Self::Prd(a, b) => a.approximate(cost).mul(&b.approximate(cost)),
+            Self::Rat(a, b) => a.approximate(cost).div(&b.approximate(cost)),
+            Self::Pow(a, b) => a.approximate(cost).pow(&b.approximate(cost)),
+            Self::Sqrt(a) => a.approximate(cost).sqrt(),
+            Self::Sin(a) => a.approximate(cost).sin(),
+            Self::Cos(a) => a.approximate(cost).cos(),
+            Self::Tan(a) => a.approximate(cost).tan(),
+            Self::Exp(a) => a.approximate(cost).exp(),
+            Self::Ln(a) => a.approximate(cost).ln(),
+            Self::Abs(a) => a.approximate(cost).abs(),
+            Self::Sign(a) => a.approximate(cost).sign(),
+            Self::Min(a, b) => a.approximate(cost).min(&b.approximate(cost)),
+            Self::Max(a, b) => a.approximate(cost).max(&b.approximate(cost)),
+            Self::If(c, a, b) => Self::piecewise(
+                &c.approximate(cost),
+                &a.approximate(cost),
+                &b.approximate(cost),
+            ),
+            _ => self.clone(),
+        }
+    }
+
     // Shortcuts:
     pub fn iadd(&self, a: i64) -> Self {
         self.add(&a.into())
@@ -214,6 +528,73 @@ impl Angle {
         Self::turn().idiv(a)
     }
 
+    /// Approximate angle of `x` radians.
+    fn float(x: f64) -> Self {
+        Self::Pi(Box::new(Val::Float(x / PI)))
+    }
+
+    /// The angle in radians, if it is an approximate multiple of pi.
+    fn as_float(&self) -> Option<f64> {
+        match self {
+            Self::Pi(x) => match **x {
+                Val::Float(x) => Some(x * PI),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Angle of `a` full turns.
+    pub fn from_turns(a: &Val) -> Self {
+        Self::Pi(Box::new(a.imul(2)))
+    }
+
+    /// Angle of `a` degrees.
+    pub fn from_degrees(a: &Val) -> Self {
+        Self::Pi(Box::new(a.idiv(180)))
+    }
+
+    /// Converts the angle to degrees.
+    /// Exact for rational multiples of pi, otherwise as precise as `to_f64`.
+    pub fn to_degrees(&self) -> Option<f64> {
+        match self.to_rational() {
+            Some(x) => Val::from(x * BigRational::from_integer(180.into())).to_f64(),
+            None => self.to_f64().map(f64::to_degrees),
+        }
+    }
+
+    /// The coefficient of pi, if this angle is a rational multiple of pi.
+    pub fn to_rational(&self) -> Option<BigRational> {
+        match self {
+            Self::Pi(a) => a.to_rational(),
+            _ => None,
+        }
+    }
+
+    /// Reduces a rational multiple of pi into [0, 2pi).
+    /// Other angles are returned unchanged.
+    pub fn reduce(&self) -> Self {
+        match self.to_rational() {
+            Some(x) => {
+                let two = BigRational::from_integer(2.into());
+                let r = &x - (&x / &two).floor() * &two;
+                Self::Pi(Box::new(r.into()))
+            }
+            None => self.clone(),
+        }
+    }
+
+    /// Reduces a rational multiple of pi into (-pi, pi].
+    /// Other angles are returned unchanged.
+    pub fn reduce_signed(&self) -> Self {
+        match self.reduce() {
+            Self::Pi(x) if x.to_rational().is_some_and(|x| x > BigRational::one()) => {
+                Self::Pi(Box::new(x.isub(2)))
+            }
+            a => a,
+        }
+    }
+
     /// Adds another angle to this one.
     pub fn add(&self, a: &Self) -> Self {
         if self.is_zero() {
@@ -263,6 +644,8 @@ impl Angle {
     pub fn sin(&self) -> Val {
         if self.is_zero() {
             0.into()
+        } else if let Some(x) = self.as_float() {
+            Val::Float(x.sin())
         } else {
             Val::Sin(self.clone())
         }
@@ -271,6 +654,8 @@ impl Angle {
     pub fn cos(&self) -> Val {
         if self.is_zero() {
             1.into()
+        } else if let Some(x) = self.as_float() {
+            Val::Float(x.cos())
         } else {
             Val::Cos(self.clone())
         }
@@ -279,11 +664,70 @@ impl Angle {
     pub fn tan(&self) -> Val {
         if self.is_zero() {
             0.into()
+        } else if let Some(x) = self.as_float() {
+            Val::Float(x.tan())
         } else {
             Val::Tan(self.clone())
         }
     }
 
+    /// Checks if any parameter appears in this angle.
+    pub fn has_param(&self) -> bool {
+        match self {
+            Self::Pi(a) | Self::ASin(a) | Self::ACos(a) | Self::ATan(a) => a.has_param(),
+            Self::Sum(a, b) | Self::Dif(a, b) => a.has_param() || b.has_param(),
+            Self::Prd(a, b) | Self::Rat(a, b) => a.has_param() || b.has_param(),
+            Self::ATan2(a, b) => a.has_param() || b.has_param(),
+        }
+    }
+
+    /// Number of nodes in the expression tree.
+    pub fn cost(&self) -> usize {
+        match self {
+            Self::Pi(a) | Self::ASin(a) | Self::ACos(a) | Self::ATan(a) => 1 + a.cost(),
+            Self::Sum(a, b) | Self::Dif(a, b) => 1 + a.cost() + b.cost(),
+            Self::Prd(a, b) | Self::Rat(a, b) => 1 + a.cost() + b.cost(),
+            Self::ATan2(a, b) => 1 + a.cost() + b.cost(),
+        }
+    }
+
+    /// Replaces parameter `t` with the value `v`.
+    pub fn subs(&self, t: usize, v: &Val) -> Self {
+        match self {
+            Self::Pi(a) => Self::Pi(Box::new(a.subs(t, v))),
+            Self::Sum(a, b) => a.subs(t, v).add(&b.subs(t, v)),
+            Self::Dif(a, b) => a.subs(t, v).sub(&b.subs(t, v)),
+            Self::Prd(a, b) => a.subs(t, v).mul(&b.subs(t, v)),
+            Self::Rat(a, b) => a.subs(t, v).div(&b.subs(t, v)),
+            Self::ASin(a) => a.subs(t, v).asin(),
+            Self::ACos(a) => a.subs(t, v).acos(),
+            Self::ATan(a) => a.subs(t, v).atan(),
+            Self::ATan2(a, b) => a.subs(t, v).atan2(&b.subs(t, v)),
+        }
+    }
+
+    /// Collapses parameter-free subtrees costing more than `cost` nodes into float multiples of pi.
+    pub fn approximate(&self, cost: usize) -> Self {
+        if !self.has_param() {
+            return if self.cost() > cost {
+                Self::Pi(Box::new(Val::Float(self.to_f64().unwrap_or(f64::NAN) / PI)))
+            } else {
+                self.clone()
+            };
+        }
+        match self {
+            Self::Pi(a) => Self::Pi(Box::new(a.approximate(cost))),
+            Self::Sum(a, b) => a.approximate(cost).add(&b.approximate(cost)),
+            Self::Dif(a, b) => a.approximate(cost).sub(&b.approximate(cost)),
+            Self::Prd(a, b) => a.approximate(cost).mul(&b.approximate(cost)),
+            Self::Rat(a, b) => a.approximate(cost).div(&b.approximate(cost)),
+            Self::ASin(a) => a.approximate(cost).asin(),
+            Self::ACos(a) => a.approximate(cost).acos(),
+            Self::ATan(a) => a.approximate(cost).atan(),
+            Self::ATan2(a, b) => a.approximate(cost).atan2(&b.approximate(cost)),
+        }
+    }
+
     /// Checks if this angle is the literal zero angle.
     /// Currently doesn't return true for e.g. 2n*pi for all integer n.
     pub fn is_zero(&self) -> bool {
@@ -302,12 +746,147 @@ impl Angle {
     }
 }
 
+impl Cond {
+    /// a < b
+    pub fn lt(a: &Val, b: &Val) -> Self {
+        Self::Lt(Box::new(a.clone()), Box::new(b.clone()))
+    }
+
+    /// a <= b
+    pub fn le(a: &Val, b: &Val) -> Self {
+        Self::Le(Box::new(a.clone()), Box::new(b.clone()))
+    }
+
+    /// a = b
+    pub fn equal(a: &Val, b: &Val) -> Self {
+        Self::Eq(Box::new(a.clone()), Box::new(b.clone()))
+    }
+
+    pub fn and(&self, c: &Self) -> Self {
+        Self::And(Box::new(self.clone()), Box::new(c.clone()))
+    }
+
+    pub fn or(&self, c: &Self) -> Self {
+        Self::Or(Box::new(self.clone()), Box::new(c.clone()))
+    }
+
+    pub fn negate(&self) -> Self {
+        Self::Not(Box::new(self.clone()))
+    }
+
+    /// Decides the condition exactly, if both sides of each comparison are rational.
+    pub fn to_exact(&self) -> Option<bool> {
+        let cmp = |a: &Val, b: &Val| a.to_rational().zip(b.to_rational());
+        match self {
+            Self::Lt(a, b) => cmp(a, b).map(|(x, y)| x < y),
+            Self::Le(a, b) => cmp(a, b).map(|(x, y)| x <= y),
+            Self::Eq(a, b) => cmp(a, b).map(|(x, y)| x == y),
+            Self::And(a, b) => match (a.to_exact(), b.to_exact()) {
+                (Some(false), _) | (_, Some(false)) => Some(false),
+                (x, y) => x.and(y),
+            },
+            Self::Or(a, b) => match (a.to_exact(), b.to_exact()) {
+                (Some(true), _) | (_, Some(true)) => Some(true),
+                (x, y) => x.or(y),
+            },
+            Self::Not(a) => a.to_exact().map(|x| !x),
+        }
+    }
+
+    /// Evaluates the condition using floats.
+    pub fn to_bool(&self) -> Option<bool> {
+        let cmp = |a: &Val, b: &Val| a.to_f64().zip(b.to_f64());
+        match self {
+            Self::Lt(a, b) => cmp(a, b).map(|(x, y)| x < y),
+            Self::Le(a, b) => cmp(a, b).map(|(x, y)| x <= y),
+            Self::Eq(a, b) => cmp(a, b).map(|(x, y)| x == y),
+            Self::And(a, b) => a.to_bool().zip(b.to_bool()).map(|(x, y)| x && y),
+            Self::Or(a, b) => a.to_bool().zip(b.to_bool()).map(|(x, y)| x || y),
+            Self::Not(a) => a.to_bool().map(|x| !x),
+        }
+    }
+
+    pub fn has_param(&self) -> bool {
+        match self {
+            Self::Lt(a, b) | Self::Le(a, b) | Self::Eq(a, b) => a.has_param() || b.has_param(),
+            Self::And(a, b) | Self::Or(a, b) => a.has_param() || b.has_param(),
+            Self::Not(a) => a.has_param(),
+        }
+    }
+
+    pub fn cost(&self) -> usize {
+        match self {
+            Self::Lt(a, b) | Self::Le(a, b) | Self::Eq(a, b) => 1 + a.cost() + b.cost(),
+            Self::And(a, b) | Self::Or(a, b) => 1 + a.cost() + b.cost(),
+            Self::Not(a) => 1 + a.cost(),
+        }
+    }
+
+    /// Replaces parameter `t` with the value `v`.
+    pub fn subs(&self, t: usize, v: &Val) -> Self {
+        self.map(&|a| a.subs(t, v))
+    }
+
+    /// Approximates both sides of each comparison, see `Val::approximate`.
+    pub fn approximate(&self, cost: usize) -> Self {
+        self.map(&|a| a.approximate(cost))
+    }
+
+    /// Applies `f` to both sides of each comparison.
+    pub fn map(&self, f: &impl Fn(&Val) -> Val) -> Self {
+        match self {
+            Self::Lt(a, b) => Self::lt(&f(a), &f(b)),
+            Self::Le(a, b) => Self::le(&f(a), &f(b)),
+            Self::Eq(a, b) => Self::equal(&f(a), &f(b)),
+            Self::And(a, b) => a.map(f).and(&b.map(f)),
+            Self::Or(a, b) => a.map(f).or(&b.map(f)),
+            Self::Not(a) => a.map(f).negate(),
+        }
+    }
+}
+
+/// Arcsine of a rational as a rational multiple of pi, where that exists (Niven's theorem).
+fn asin_pi(x: &BigRational) -> Option<BigRational> {
+    let half = BigRational::new(1.into(), 2.into());
+    if x.abs() == BigRational::one() {
+        Some(&half * x)
+    } else if x.abs() == half {
+        Some(x / BigRational::from_integer(3.into()))
+    } else if x.is_zero() {
+        Some(x.clone())
+    } else {
+        None
+    }
+}
+
+/// Square root of a rational, if it is itself rational.
+fn sqrt_exact(x: &BigRational) -> Option<BigRational> {
+    if x.is_negative() {
+        return None;
+    }
+    let (n, d) = (x.numer().sqrt(), x.denom().sqrt());
+    (&n * &n == *x.numer() && &d * &d == *x.denom()).then(|| BigRational::new(n, d))
+}
+
 impl From<i64> for Val {
     fn from(item: i64) -> Self {
         Val::Int(item.into())
     }
 }
 
+impl From<BigRational> for Val {
+    fn from(item: BigRational) -> Self {
+        let (n, d) = item.into_raw();
+        Val::Int(n).div(&Val::Int(d))
+    }
+}
+
+impl From<f64> for Val {
+    fn from(item: f64) -> Self {
+        Val::Float(item)
+    }
+}
+
 impl ToPrimitive for Val {
     fn to_i64(&self) -> Option<i64> {
         match self {
@@ -321,6 +900,14 @@ impl ToPrimitive for Val {
                     Err(_) => None,
                 })
             }),
+            Self::Abs(a) => a.to_i64().map(|x| x.abs()),
+            Self::Sign(a) => a.to_i64().map(|x| x.signum()),
+            Self::Min(a, b) => a.to_i64().and_then(|x| b.to_i64().map(|y| x.min(y))),
+            Self::Max(a, b) => a.to_i64().and_then(|x| b.to_i64().map(|y| x.max(y))),
+            Self::If(c, a, b) => match c.to_bool()? {
+                true => a.to_i64(),
+                false => b.to_i64(),
+            },
             _ => None,
         }
     }
@@ -337,6 +924,14 @@ impl ToPrimitive for Val {
                     Err(_) => None,
                 })
             }),
+            Self::Abs(a) => a.to_i64().map(|x| x.unsigned_abs()),
+            Self::Sign(_) | Self::Min(..) | Self::Max(..) => {
+                self.to_i64().and_then(|x| x.try_into().ok())
+            }
+            Self::If(c, a, b) => match c.to_bool()? {
+                true => a.to_u64(),
+                false => b.to_u64(),
+            },
             _ => None,
         }
     }
@@ -346,15 +941,27 @@ impl ToPrimitive for Val {
     fn to_f64(&self) -> Option<f64> {
         match self {
             Self::Int(a) => a.to_f64(),
+            Self::Float(a) => Some(*a),
             Self::Sum(a, b) => a.to_f64().and_then(|x| b.to_f64().map(|y| x + y)),
             Self::Dif(a, b) => a.to_f64().and_then(|x| b.to_f64().map(|y| x - y)),
             Self::Prd(a, b) => a.to_f64().and_then(|x| b.to_f64().map(|y| x * y)),
             Self::Rat(a, b) => a.to_f64().and_then(|x| b.to_f64().map(|y| x / y)),
             Self::Pow(a, b) => a.to_f64().and_then(|x| b.to_f64().map(|y| x.powf(y))),
-            Self::Sqrt(a) => a.to_f64().and_then(|x| Some(x.sqrt())),
-            Self::Sin(a) => a.to_f64().and_then(|x| Some(x.sin())),
-            Self::Cos(a) => a.to_f64().and_then(|x| Some(x.cos())),
-            Self::Tan(a) => a.to_f64().and_then(|x| Some(x.tan())),
+            Self::Sqrt(a) => a.to_f64().map(|x| x.sqrt()),
+            Self::Sin(a) => a.to_f64().map(|x| x.sin()),
+            Self::Cos(a) => a.to_f64().map(|x| x.cos()),
+            Self::Tan(a) => a.to_f64().map(|x| x.tan()),
+            Self::Exp(a) => a.to_f64().map(|x| x.exp()),
+            Self::Ln(a) => a.to_f64().map(|x| x.ln()),
+            Self::Abs(a) => a.to_f64().map(|x| x.abs()),
+            // Unlike f64::signum, this is zero at zero, as in JS.
+            Self::Sign(a) => a.to_f64().map(|x| if x == 0.0 { x } else { x.signum() }),
+            Self::Min(a, b) => a.to_f64().and_then(|x| b.to_f64().map(|y| x.min(y))),
+            Self::Max(a, b) => a.to_f64().and_then(|x| b.to_f64().map(|y| x.max(y))),
+            Self::If(c, a, b) => match c.to_bool()? {
+                true => a.to_f64(),
+                false => b.to_f64(),
+            },
             Self::Param(_) => None,
         }
     }
@@ -362,25 +969,26 @@ impl ToPrimitive for Val {
 
 impl ToPrimitive for Angle {
     fn to_i64(&self) -> Option<i64> {
-        self.to_f64().and_then(|x| Some(x as i64))
+        self.to_f64().map(|x| x as i64)
     }
 
     fn to_u64(&self) -> Option<u64> {
-        self.to_i64().and_then(|x| Some(x as u64))
+        self.to_i64().map(|x| x as u64)
     }
 
     /// Converts the value to a float.
     /// No efforts are made for numeric stability; use only for debugging.
     fn to_f64(&self) -> Option<f64> {
         match self {
-            Self::Pi(a) => a.to_f64().and_then(|x| Some(PI * x)),
+            Self::Pi(a) => a.to_f64().map(|x| PI * x),
             Self::Sum(a, b) => a.to_f64().and_then(|x| b.to_f64().map(|y| x + y)),
             Self::Dif(a, b) => a.to_f64().and_then(|x| b.to_f64().map(|y| x - y)),
             Self::Prd(a, b) => a.to_f64().and_then(|x| b.to_f64().map(|y| x * y)),
             Self::Rat(a, b) => a.to_f64().and_then(|x| b.to_f64().map(|y| x / y)),
-            Self::ASin(a) => a.to_f64().and_then(|x| Some(x.asin())),
-            Self::ACos(a) => a.to_f64().and_then(|x| Some(x.acos())),
-            Self::ATan(a) => a.to_f64().and_then(|x| Some(x.atan())),
+            Self::ASin(a) => a.to_f64().map(|x| x.asin()),
+            Self::ACos(a) => a.to_f64().map(|x| x.acos()),
+            Self::ATan(a) => a.to_f64().map(|x| x.atan()),
+            Self::ATan2(a, b) => a.to_f64().and_then(|y| b.to_f64().map(|x| y.atan2(x))),
         }
     }
 }
@@ -389,6 +997,9 @@ impl fmt::Display for Val {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::Int(a) => write!(f, "{}", a),
+            // Debug formatting keeps the decimal point and full precision.
+            Self::Float(a) if *a < 0.0 => write!(f, "({:?})", a),
+            Self::Float(a) => write!(f, "{:?}", a),
             Self::Sum(a, b) => write!(f, "({}+{})", a, b),
             Self::Dif(a, b) => write!(f, "({}-{})", a, b),
             Self::Prd(a, b) => write!(f, "({}*{})", a, b),
@@ -398,6 +1009,13 @@ impl fmt::Display for Val {
             Self::Sin(a) => write!(f, "sin({})", a),
             Self::Cos(a) => write!(f, "cos({})", a),
             Self::Tan(a) => write!(f, "tan({})", a),
+            Self::Exp(a) => write!(f, "exp({})", a),
+            Self::Ln(a) => write!(f, "log({})", a),
+            Self::Abs(a) => write!(f, "abs({})", a),
+            Self::Sign(a) => write!(f, "sign({})", a),
+            Self::Min(a, b) => write!(f, "min({},{})", a, b),
+            Self::Max(a, b) => write!(f, "max({},{})", a, b),
+            Self::If(c, a, b) => write!(f, "({}?{}:{})", c, a, b),
             Self::Param(t) => {
                 if *t == 0 {
                     write!(f, "t")
@@ -426,6 +1044,193 @@ impl fmt::Display for Angle {
             Self::ASin(a) => write!(f, "asin({})", a),
             Self::ACos(a) => write!(f, "acos({})", a),
             Self::ATan(a) => write!(f, "atan({})", a),
+            Self::ATan2(a, b) => write!(f, "atan2({},{})", a, b),
+        }
+    }
+}
+
+impl fmt::Display for Cond {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Lt(a, b) => write!(f, "({}<{})", a, b),
+            Self::Le(a, b) => write!(f, "({}<={})", a, b),
+            Self::Eq(a, b) => write!(f, "({}=={})", a, b),
+            Self::And(a, b) => write!(f, "({}&&{})", a, b),
+            Self::Or(a, b) => write!(f, "({}||{})", a, b),
+            Self::Not(a) => write!(f, "!{}", a),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_approximate_param_free() {
+        let v = Val::from(5).sqrt().iadd(1).idiv(2);
+        assert!(matches!(v.approximate(0), Val::Float(_)));
+        assert_relative_eq!(v.approximate(0).to_f64().unwrap(), v.to_f64().unwrap());
+
+        // Cheap subtrees are kept exact.
+        assert!(matches!(v.approximate(v.cost()), Val::Rat(_, _)));
+    }
+
+    #[test]
+    fn test_approximate_keeps_params() {
+        let t = Val::param(1);
+        let v = Val::from(5)
+            .sqrt()
+            .iadd(1)
+            .idiv(2)
+            .mul(&t)
+            .add(&Val::from(2).sqrt());
+
+        let a = v.approximate(1);
+        assert!(a.has_param());
+        assert_eq!(a.cost(), 5);
+        assert_relative_eq!(
+            a.subs(1, &3.into()).to_f64().unwrap(),
+            v.subs(1, &3.into()).to_f64().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_approximate_angle() {
+        let a = Val::from(5).sqrt().idiv(5).acos().mul(&Val::param(1));
+        assert_eq!(
+            format!("{}", a.subs(1, &2.into()).approximate(0)),
+            "0.7048327646991335*PI"
+        );
+    }
+
+    #[test]
+    fn test_float_display() {
+        assert_eq!(
+            format!("{}", Val::param(1).sub(&Val::from(1.5))),
+            "(t_1+(-1.5))"
+        );
+        assert_eq!(format!("{}", Val::from(2.0)), "2.0");
+    }
+
+    #[test]
+    fn test_float_folding() {
+        let x = Val::from(0.5);
+        assert!(matches!(x.div(&2.into()), Val::Float(y) if y == 0.25));
+        assert!(matches!(x.ipow(2), Val::Float(y) if y == 0.25));
+        assert!(matches!(Val::from(0.25).sqrt(), Val::Float(y) if y == 0.5));
+        assert!(matches!(x.asin().sin(), Val::Float(y) if y == 0.5f64.asin().sin()));
+        assert!(matches!(x.atan2(&1.into()).cos(), Val::Float(_)));
+    }
+
+    #[test]
+    fn test_atan2() {
+        for (y, x) in [
+            (1, 2),
+            (-1, 2),
+            (1, -2),
+            (-1, -2),
+            (0, 1),
+            (0, -1),
+            (1, 0),
+            (-1, 0),
+        ] {
+            let (y, x) = (Val::from(y).idiv(3), Val::from(x));
+            assert_relative_eq!(
+                y.atan2(&x).to_f64().unwrap(),
+                y.to_f64().unwrap().atan2(x.to_f64().unwrap())
+            );
         }
+        assert_eq!(Val::from(0).atan2(&Val::from(-1)), Angle::turn().idiv(2));
+        assert_eq!(
+            format!("{}", Val::param(1).atan2(&Val::from(-1))),
+            "atan2(t_1,-1)"
+        );
+    }
+
+    #[test]
+    fn test_fns() {
+        let x = Val::from(-3).idiv(2);
+        assert_relative_eq!(x.exp().to_f64().unwrap(), (-1.5_f64).exp());
+        assert_relative_eq!(x.exp().ln().to_f64().unwrap(), -1.5);
+        assert_eq!(x.abs(), Val::from(3).idiv(2));
+        assert_eq!(x.sign(), Val::from(-1));
+        assert_eq!(Val::from(0).sign(), Val::from(0));
+        assert_eq!(x.min(&1.into()), x);
+        assert_eq!(x.max(&1.into()), Val::from(1));
+
+        let t = Val::param(1);
+        assert_eq!(format!("{}", t.abs().exp()), "exp(abs(t_1))");
+        assert_eq!(format!("{}", t.ln().sign()), "sign(log(t_1))");
+        assert_eq!(
+            format!("{}", t.min(&x).max(&0.into())),
+            "max(min(t_1,(-3/2)),0)"
+        );
+        assert_eq!(Val::Sign(Box::new(0.into())).to_f64(), Some(0.0));
+
+        // Integer conversions see through the piecewise fns.
+        let n = Val::Abs(Box::new((-3).into()));
+        assert_eq!((n.to_i64(), n.to_u64()), (Some(3), Some(3)));
+        let n = Val::Max(Box::new((-3).into()), Box::new(2.into()));
+        assert_eq!((n.to_i64(), n.to_u64()), (Some(2), Some(2)));
+        let n = Val::Sign(Box::new((-3).into()));
+        assert_eq!((n.to_i64(), n.to_u64()), (Some(-1), None));
+    }
+
+    #[test]
+    fn test_piecewise() {
+        let t = Val::param(1);
+        let v = Val::piecewise(&Cond::lt(&t, &0.into()), &t.neg(), &t);
+        assert_eq!(format!("{}", v), "((t_1<0)?(t_1*-1):t_1)");
+        assert_eq!(v.subs(1, &(-2).into()), Val::from(2));
+        assert_eq!(v.subs(1, &2.into()), Val::from(2));
+
+        let c = Cond::le(&t, &1.into()).and(&Cond::equal(&t, &1.into()).negate());
+        let v = Val::piecewise(&c, &1.into(), &0.into());
+        assert_eq!(v.subs(1, &1.into()), Val::from(0));
+        assert_eq!(v.subs(1, &Val::from(1).idiv(2)), Val::from(1));
+        assert_eq!(v.subs(1, &Val::from(0.5)).to_f64(), Some(1.0));
+    }
+
+    #[test]
+    fn test_angle_units() {
+        assert_eq!(Angle::from_degrees(&90.into()).to_degrees(), Some(90.0));
+        assert_eq!(
+            Angle::from_turns(&Val::from(1).idiv(3)).to_degrees(),
+            Some(120.0)
+        );
+        assert_relative_eq!(Val::from(1).acos().to_degrees().unwrap(), 0.0);
+        assert_relative_eq!(
+            Val::from(1).idiv(2).asin().to_degrees().unwrap(),
+            30.0,
+            epsilon = 1e-12
+        );
+    }
+
+    #[test]
+    fn test_angle_reduce() {
+        let deg = |x: i64| Angle::from_degrees(&x.into());
+        assert_eq!(deg(-90).reduce().to_degrees(), Some(270.0));
+        assert_eq!(deg(720).reduce().to_degrees(), Some(0.0));
+        assert_eq!(deg(270).reduce_signed().to_degrees(), Some(-90.0));
+        assert_eq!(deg(180).reduce_signed().to_degrees(), Some(180.0));
+        assert_eq!(deg(-180).reduce_signed().to_degrees(), Some(180.0));
+        let a = Val::param(1).pi();
+        assert_eq!(a.reduce(), a);
+    }
+
+    #[test]
+    fn test_angle_cmp() {
+        use std::cmp::Ordering::*;
+        let deg = |x: i64| Angle::from_degrees(&x.into());
+        assert_eq!(deg(30).cmp_certified(&Angle::part(12)), Some(Equal));
+        assert_eq!(deg(30).cmp_certified(&deg(31)), Some(Less));
+        let a = Val::from(1).idiv(3).asin();
+        assert_eq!(a.cmp_certified(&deg(19)), Some(Greater));
+        assert_eq!(a.cmp_certified(&deg(20)), Some(Less));
+        assert_eq!(a.cmp_certified(&Val::from(2).idiv(6).asin()), None);
+        assert_eq!(a.cmp_certified(&a), Some(Equal));
+        assert_eq!(Val::param(1).pi().cmp_certified(&deg(1)), None);
     }
 }
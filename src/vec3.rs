@@ -0,0 +1,164 @@
+use crate::scalar::{Scalar, ScalarAngle};
+use crate::spherical::Norm;
+use crate::val::Val;
+
+/// Cartesian vector.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Vec3<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+impl<T> Vec3<T> {
+    pub fn new(x: T, y: T, z: T) -> Self {
+        Self { x, y, z }
+    }
+
+    /// Components as an array, in x, y, z order.
+    pub fn to_array(self) -> [T; 3] {
+        [self.x, self.y, self.z]
+    }
+}
+
+impl<S: Scalar> Vec3<S> {
+    pub fn zero() -> Self {
+        Self::new(S::int(0), S::int(0), S::int(0))
+    }
+
+    pub fn add(&self, a: &Self) -> Self {
+        Self::new(self.x.add(&a.x), self.y.add(&a.y), self.z.add(&a.z))
+    }
+
+    pub fn sub(&self, a: &Self) -> Self {
+        Self::new(self.x.sub(&a.x), self.y.sub(&a.y), self.z.sub(&a.z))
+    }
+
+    pub fn neg(&self) -> Self {
+        Self::new(self.x.neg(), self.y.neg(), self.z.neg())
+    }
+
+    /// Multiplies each component by `a`.
+    pub fn scale(&self, a: &S) -> Self {
+        Self::new(self.x.mul(a), self.y.mul(a), self.z.mul(a))
+    }
+
+    pub fn dot(&self, a: &Self) -> S {
+        self.x
+            .mul(&a.x)
+            .add(&self.y.mul(&a.y))
+            .add(&self.z.mul(&a.z))
+    }
+
+    /// Right-handed cross product.
+    pub fn cross(&self, a: &Self) -> Self {
+        Self::new(
+            self.y.mul(&a.z).sub(&self.z.mul(&a.y)),
+            self.z.mul(&a.x).sub(&self.x.mul(&a.z)),
+            self.x.mul(&a.y).sub(&self.y.mul(&a.x)),
+        )
+    }
+
+    /// Squared length, avoiding the square root.
+    pub fn norm_sq(&self) -> S {
+        self.dot(self)
+    }
+
+    /// Length of the vector.
+    pub fn norm(&self) -> S {
+        self.norm_sq().sqrt()
+    }
+
+    /// Vector of unit length, pointing in the same direction.
+    pub fn normalise(&self) -> Self {
+        self.scale(&S::int(1).div(&self.norm()))
+    }
+
+    /// Direction of the vector as spherical coordinates; the length is dropped.
+    /// On the poles, where the azimuth is undefined, it is set to zero.
+    pub fn to_norm(&self) -> Norm<S> {
+        let zero = |v: &S| v.simplify().is_zero();
+        let phi = if zero(&self.x) && zero(&self.y) {
+            S::Angle::zero()
+        } else {
+            self.y.atan2(&self.x)
+        };
+        Norm::new(
+            self.x.ipow(2).add(&self.y.ipow(2)).sqrt().atan2(&self.z),
+            phi,
+        )
+    }
+
+    /// Simplifies each component, see `Scalar::simplify`.
+    pub fn simplify(&self) -> Self {
+        Self::new(self.x.simplify(), self.y.simplify(), self.z.simplify())
+    }
+}
+
+impl Vec3<Val> {
+    pub fn simplify_trig(&self) -> Self {
+        Self::new(
+            self.x.simplify_trig(),
+            self.y.simplify_trig(),
+            self.z.simplify_trig(),
+        )
+    }
+}
+
+impl<S: Scalar> From<&Norm<S>> for Vec3<S> {
+    fn from(n: &Norm<S>) -> Self {
+        Self::new(n.x(), n.y(), n.z())
+    }
+}
+
+impl<S: Scalar> From<Norm<S>> for Vec3<S> {
+    fn from(n: Norm<S>) -> Self {
+        Self::from(&n)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::alpha;
+    use crate::val::Angle;
+    use approx::assert_relative_eq;
+    use num_traits::{One, ToPrimitive};
+
+    fn assert_vec_eq(a: &Vec3<Val>, b: &Vec3<Val>) {
+        for (x, y) in a.clone().to_array().iter().zip(b.clone().to_array().iter()) {
+            assert_relative_eq!(x.to_f64().unwrap(), y.to_f64().unwrap(), epsilon = 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_products() {
+        let x: Vec3<Val> = Vec3::new(1.into(), 0.into(), 0.into());
+        let y = Vec3::new(0.into(), 1.into(), 0.into());
+        let z = Vec3::new(0.into(), 0.into(), 1.into());
+        assert_eq!(x.cross(&y), z);
+        assert_eq!(y.cross(&x), z.neg());
+        assert_eq!(x.dot(&y), Val::from(0));
+
+        let v: Vec3<Val> = Vec3::new(3.into(), 4.into(), 12.into());
+        assert_eq!(v.norm(), Val::from(13));
+        assert!(v.normalise().norm_sq().to_rational().unwrap().is_one());
+        assert_eq!(v.sub(&v), Vec3::zero());
+        assert_eq!(v.add(&v), v.scale(&2.into()));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let n: Norm = Norm::zero().south(&alpha()).east(&Angle::part(5).imul(3));
+        let v = Vec3::from(&n);
+        assert_relative_eq!(v.norm().to_f64().unwrap(), 1.0, epsilon = 1e-15);
+        assert_vec_eq(&Vec3::from(v.to_norm()), &v);
+
+        // Length is dropped; exact directions stay exact.
+        let v: Vec3<Val> = Vec3::new(0.into(), (-2).into(), 0.into());
+        let n = v.to_norm();
+        assert_eq!(n.theta().to_rational(), Angle::part(4).to_rational());
+        assert_eq!(n.phi().to_rational(), Angle::part(4).imul(3).to_rational());
+        assert_vec_eq(&Vec3::from(n), &v.normalise());
+    }
+}
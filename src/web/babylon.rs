@@ -1,4 +1,4 @@
-use crate::{alpha, Angle, Norm};
+use crate::{alpha, Angle, Norm, Val, Vec3};
 use rocket::serde::Serialize;
 
 #[derive(Serialize)]
@@ -18,26 +18,41 @@ impl Consts {
         let q = z.clone().south(&alpha());
         let r = q.clone().east(&Angle::turn().div(&5.into()));
 
-        let ox = format!("{} + {}", q.x(), r.x());
-        let oy = r.y().to_string();
-        let oz = format!("{} + {} + {}", q.z(), r.z(), z.z());
+        let o = Vec3::from(&z).add(&q.into()).add(&r.clone().into());
 
         Self {
             x: vec!["1".into(), "0".into(), "0".into()],
             y: vec!["0".into(), "1".into(), "0".into()],
             z: vec!["0".into(), "0".into(), "1".into()],
             r: xyz(vec![r]),
-            o: vec![ox, oz, oy],
+            o: babylon(o),
         }
     }
 }
 
+impl Default for Consts {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 pub fn xyz(points: Vec<Norm>) -> Vec<String> {
+    points.into_iter().flat_map(|n| babylon(n.into())).collect()
+}
+
+/// Numeric points, e.g. from a generated mesh.
+pub fn xyz_f64(points: &[Vec3<f64>]) -> Vec<String> {
+    // BABYLON is Y-up left-handed.
     points
-        .into_iter()
-        // BABYLON is Y-up left-handed.
-        .map(|n| [n.x(), n.z(), n.y()])
-        .flatten()
-        .map(|v| format!("{}", v))
+        .iter()
+        .flat_map(|v| [v.x, v.z, v.y].map(|c| c.to_string()))
+        .collect()
+}
+
+fn babylon(v: Vec3<Val>) -> Vec<String> {
+    // BABYLON is Y-up left-handed.
+    [v.x, v.z, v.y]
+        .iter()
+        .map(|v| format!("{}", v.simplify_trig()))
         .collect()
 }
@@ -1,6 +1,9 @@
-use super::babylon::xyz;
-use crate::{alpha, beta, Angle, Norm, Val};
-use rocket::serde::Serialize;
+use super::babylon::{xyz, xyz_f64};
+use crate::{
+    alpha, beta, mesh, Angle, FaceKind, Goldberg, GreatCircle, Norm, Notation, Param, SmallCircle,
+    Stellation, Val, Vec3, Wythoff,
+};
+use rocket::serde::{Serialize, Serializer};
 
 #[derive(Serialize)]
 #[serde(crate = "rocket::serde")]
@@ -14,10 +17,120 @@ pub struct Mesh {
 #[serde(crate = "rocket::serde")]
 pub struct Geometry {
     meshes: Vec<Mesh>,
-    params: Vec<String>,
+    /// Declarations of the parameters used in mesh positions, ordered by index from 1.
+    #[serde(serialize_with = "sliders")]
+    params: Vec<Param>,
+}
+
+impl Mesh {
+    /// Points with polygon faces, given as vertex indices.
+    fn new(points: Vec<Norm>, faces: &[Vec<usize>], symmetry: &str) -> Self {
+        let mesh = mesh::Mesh::from_faces(points, faces).expect("consistent winding");
+        Self {
+            positions: xyz(mesh.positions().to_vec()),
+            indices: mesh.indices(),
+            symmetry: symmetry.into(),
+        }
+    }
+}
+
+impl Mesh {
+    /// Some of the faces of a full, numeric mesh.
+    fn part(mesh: &mesh::Mesh<Vec3<f64>>, filter: impl Fn(usize) -> bool) -> Self {
+        let faces: Vec<Vec<usize>> = mesh
+            .faces()
+            .filter(|&f| filter(f))
+            .map(|f| mesh.face_vertices(f))
+            .collect();
+        let part =
+            mesh::Mesh::from_faces(mesh.positions().to_vec(), &faces).expect("consistent winding");
+        Self {
+            positions: xyz_f64(mesh.positions()),
+            indices: part.indices(),
+            symmetry: "none".into(),
+        }
+    }
+}
+
+/// A parameter as the viewer shows it, by the symbol used in the formulas.
+#[derive(Serialize)]
+#[serde(crate = "rocket::serde")]
+struct Slider<'a> {
+    index: usize,
+    name: &'a str,
+    symbol: String,
+    default: String,
+    min: f64,
+    max: f64,
+    step: f64,
+    description: &'a str,
+    unit: &'a str,
+}
+
+impl<'a> From<&'a Param> for Slider<'a> {
+    fn from(p: &'a Param) -> Self {
+        Self {
+            index: p.index,
+            name: &p.name,
+            symbol: format!("{}", p.val()),
+            default: format!("{}", p.default),
+            min: p.min,
+            max: p.max,
+            step: p.step,
+            description: &p.description,
+            unit: &p.unit,
+        }
+    }
+}
+
+fn sliders<S: Serializer>(params: &[Param], s: S) -> Result<S::Ok, S::Error> {
+    s.collect_seq(params.iter().map(Slider::from))
 }
 
 impl Geometry {
+    /// The full GP(m, n), with pentagons and hexagons as separate meshes; `None` for GP(0, 0).
+    pub fn goldberg(m: usize, n: usize) -> Option<Self> {
+        let g = Goldberg::<f64>::new(m, n)?;
+        Some(Self {
+            meshes: [FaceKind::Pentagon, FaceKind::Hexagon]
+                .into_iter()
+                .map(|kind| Mesh::part(g.mesh(), |f| g.kind(f) == kind))
+                .collect(),
+            params: vec![],
+        })
+    }
+
+    /// A polyhedron in Conway notation, with a separate mesh for each number of sides.
+    pub fn conway(notation: &Notation) -> Self {
+        Self::by_sides(&notation.mesh())
+    }
+
+    /// The uniform polyhedron of a Wythoff symbol, with a separate mesh for each number of sides.
+    pub fn wythoff(symbol: &Wythoff) -> Self {
+        Self::by_sides(&symbol.polyhedron())
+    }
+
+    /// The visible surface of a stellation of the icosahedron.
+    pub fn stellation(stellation: &Stellation) -> Self {
+        Self {
+            meshes: vec![Mesh::part(&stellation.mesh(), |_| true)],
+            params: vec![],
+        }
+    }
+
+    fn by_sides(m: &mesh::Mesh<Vec3<f64>>) -> Self {
+        let mut sides: Vec<usize> = m.faces().map(|f| m.face_vertices(f).len()).collect();
+        sides.sort();
+        sides.dedup();
+        Self {
+            meshes: sides
+                .into_iter()
+                .map(|n| Mesh::part(m, |f| m.face_vertices(f).len() == n))
+                .collect(),
+            params: vec![],
+        }
+    }
+
     pub fn goldberg_1_0() -> Self {
         let fifth = Angle::part(5);
         let tenth = Angle::part(10);
@@ -25,28 +138,29 @@ impl Geometry {
         let top = Norm::zero();
 
         Self {
-            meshes: vec![Mesh {
-                positions: xyz(((0 as i64)..5)
-                    .into_iter()
+            meshes: vec![Mesh::new(
+                (0..5_i64)
                     .map(|i| top.south(&beta()).east(&tenth).east(&fifth.imul(i)))
-                    .collect()),
-                indices: vec![0, 1, 2, 2, 3, 0, 0, 3, 4],
-                symmetry: "icos.v.1".into(),
-            }],
+                    .collect(),
+                &[vec![0, 1, 2, 3, 4]],
+                "icos.v.1",
+            )],
             params: vec![],
         }
     }
 
     pub fn goldberg_1_1() -> Self {
-        let t = Val::param(1);
-        let by = alpha().mul(&t).idiv(2);
+        let t = Param::new(1, "Truncation")
+            .default(&Val::from(0.6329870724964068))
+            .description(
+                "Angular distance of the pentagon vertices from the icosahedron vertex, as a fraction of half an edge.",
+            );
+        let by = alpha().mul(&t.val()).idiv(2);
 
         let fifth = Angle::turn().idiv(5);
 
         let top = Norm::zero();
-        let pentagon = ((0 as i64)..5)
-            .into_iter()
-            .map(|i| top.south(&by).east(&fifth.imul(i)));
+        let pentagon = (0..5_i64).map(|i| top.south(&by).east(&fifth.imul(i)));
 
         let r_0_0 = top.south(&by);
         let r_0_1 = r_0_0.east(&fifth);
@@ -54,29 +168,25 @@ impl Geometry {
 
         Self {
             meshes: vec![
-                Mesh {
-                    positions: xyz(pentagon.collect()),
-                    indices: vec![0, 1, 2, 2, 3, 0, 0, 3, 4],
-                    symmetry: "icos.v.1".into(),
-                },
-                Mesh {
-                    positions: xyz(vec![r_0_0, r_0_1.clone(), r_1_0]),
-                    indices: vec![0, 2, 1],
-                    symmetry: "icos.f.3".into(),
-                },
-                Mesh {
-                    positions: xyz(vec![r_0_1]),
-                    indices: vec![],
-                    symmetry: "icos.f.c".into(),
-                },
+                Mesh::new(pentagon.collect(), &[vec![0, 1, 2, 3, 4]], "icos.v.1"),
+                Mesh::new(
+                    vec![r_0_0, r_0_1.clone(), r_1_0],
+                    &[vec![0, 2, 1]],
+                    "icos.f.3",
+                ),
+                Mesh::new(vec![r_0_1], &[], "icos.f.c"),
             ],
-            params: vec!["0.6329870724964068".into()],
+            params: vec![t],
         }
     }
 
     pub fn goldberg_2_0() -> Self {
-        let t = Val::param(1);
-        let by = beta().mul(&t);
+        let t = Param::new(1, "Pentagon size")
+            .default(&Val::from(0.42))
+            .description(
+                "Angular distance of the pentagon vertices from its centre, as a fraction of the dodecahedron's.",
+            );
+        let by = beta().mul(&t.val());
 
         let fifth = Angle::part(5);
         let tenth = Angle::part(10);
@@ -84,59 +194,49 @@ impl Geometry {
         let o = Norm::zero().south(&beta()).east(&tenth);
         let r_0 = Norm::zero().south(&by).east(&tenth);
 
-        // DBG: How to calculate this third point?
-        let dbg = Norm::zero().south(&Angle::turn().mul(&Val::param(2))).rot(
-            &Norm::zero().south(&Angle::part(4)).east(&Angle::part(5)),
-            &Angle::turn().mul(&Val::param(3)),
-        );
+        // Hexagon vertex in the corner at v_1: on the median through v_1,
+        // and the same distance from the face centre as r_0 (which is on the median through the top).
+        // Constant subtrees are approximated to keep the intersection small.
+        let v_1 = Norm::zero().south(&alpha()).east(&fifth).approximate(0);
+        let centre = o.approximate(0);
+        let [_, corner] = SmallCircle::new(centre.clone(), beta().sub(&by).approximate(0))
+            .intersect_great(&GreatCircle::through(&v_1, &centre))
+            .expect("median crosses the circle");
 
         Self {
             meshes: vec![
-                Mesh {
-                    positions: xyz(vec![r_0.clone()]),
-                    indices: vec![],
-                    symmetry: "dbg".into(),
-                },
-                Mesh {
-                    positions: xyz(vec![r_0.east(&fifth)]),
-                    indices: vec![],
-                    symmetry: "dbg".into(),
-                },
-                Mesh {
-                    positions: xyz(vec![o.clone()]),
-                    indices: vec![],
-                    symmetry: "dbg".into(),
-                },
-                Mesh {
-                    positions: xyz(vec![o.east(&fifth)]),
-                    indices: vec![],
-                    symmetry: "dbg".into(),
-                },
-                Mesh {
-                    positions: xyz(vec![dbg]),
-                    indices: vec![],
-                    symmetry: "dbg".into(),
-                },
-                Mesh {
-                    positions: xyz(((0 as i64)..5)
-                        .into_iter()
-                        .map(|i| r_0.east(&fifth.imul(i)))
-                        .collect()),
-                    indices: vec![0, 1, 2, 2, 3, 0, 0, 3, 4],
-                    symmetry: "icos.v.1".into(),
-                },
-                Mesh {
-                    positions: xyz(vec![
-                        r_0.clone(),
-                        r_0.east(&fifth),
-                        o.clone(),
-                        o.east(&fifth),
-                    ]),
-                    indices: vec![1, 0, 2, 1, 2, 3],
-                    symmetry: "icos.f.3".into(),
-                },
+                Mesh::new(
+                    (0..5_i64).map(|i| r_0.east(&fifth.imul(i))).collect(),
+                    &[vec![0, 1, 2, 3, 4]],
+                    "icos.v.1",
+                ),
+                // Half a hexagon: an ear at the face centre and the triangle across the pentagon edge.
+                // The turn about the icosahedron edge gives the other half.
+                Mesh::new(
+                    vec![r_0.clone(), r_0.east(&fifth), o.clone(), corner],
+                    &[vec![0, 2, 3], vec![1, 0, 3]],
+                    "icos.f.3",
+                ),
             ],
-            params: vec!["0.42".into(), "0".into(), "0".into()],
+            params: vec![t],
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rocket::serde::json::to_string;
+
+    #[test]
+    fn test_slider() {
+        let p = Param::new(2, "Twist")
+            .default(&Val::from(1).idiv(2))
+            .range(-1.0, 1.0)
+            .unit("turn");
+        assert_eq!(
+            to_string(&Slider::from(&p)).unwrap(),
+            r#"{"index":2,"name":"Twist","symbol":"t_2","default":"(1/2)","min":-1.0,"max":1.0,"step":0.01,"description":"","unit":"turn"}"#
+        );
+    }
+}
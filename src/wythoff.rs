@@ -0,0 +1,391 @@
+use crate::mesh::{trace_faces, Mesh};
+use crate::scalar::{Scalar, ScalarAngle};
+use crate::val::Angle;
+use crate::vec3::Vec3;
+
+/// Wythoff symbol over a Schwarz triangle (2 3 n), with n = 3, 4 or 5.
+///
+/// The triangle has angles π/2, π/3 and π/n; its corners are where the 2-, 3- and n-fold axes meet
+/// the sphere, and its sides are the mirrors of the kaleidoscope.
+/// Where the generating point goes depends on the position of the bar:
+///
+/// - `p | q r`: at corner p.
+/// - `p q | r`: on side pq, as far from the two mirrors through r.
+/// - `p q r |`: inside, as far from all three mirrors.
+/// - `| p q r`: inside, so that its images rotated around the three corners are all as far from it; this gives the snubs.
+///
+/// E.g. `5 | 2 3` is the icosahedron, `2 5 | 3` the truncated icosahedron and `| 2 3 5` the snub dodecahedron.
+/// For (2 3 3), the first 3 is the triangle's corner at a face centre of the tetrahedron, the second one at a vertex.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Wythoff {
+    numbers: [i64; 3],
+    /// How many of the numbers are before the bar.
+    bar: usize,
+}
+
+impl Wythoff {
+    /// Parses e.g. `2 5 | 3`; spaces are optional, since the numbers are single digits.
+    pub fn parse(s: &str) -> Option<Self> {
+        let mut numbers = vec![];
+        let mut bar = None;
+        for c in s.chars().filter(|c| !c.is_whitespace()) {
+            if c == '|' && bar.is_none() {
+                bar = Some(numbers.len());
+            } else {
+                numbers.push(c.to_digit(10)? as i64);
+            }
+        }
+        let numbers: [i64; 3] = numbers.try_into().ok()?;
+        let mut sorted = numbers;
+        sorted.sort();
+        matches!(sorted, [2, 3, 3..=5]).then_some(Self { numbers, bar: bar? })
+    }
+
+    pub fn numbers(&self) -> [i64; 3] {
+        self.numbers
+    }
+
+    pub fn is_snub(&self) -> bool {
+        self.bar == 0
+    }
+
+    /// The n of the Schwarz triangle (2 3 n).
+    pub fn n(&self) -> i64 {
+        self.numbers.into_iter().max().expect("three numbers")
+    }
+
+    /// The uniform polyhedron, with its vertices on the unit sphere and an n-fold axis along z.
+    pub fn polyhedron<S: Scalar>(&self) -> Mesh<Vec3<S>> {
+        // Vertices are found numerically, along with the symmetries that lead to them;
+        // those are kept as integer maps, so that exact positions don't pile up reflections.
+        let n = self.n();
+        let (shadow, schwarz) = (Schwarz::<f64>::new(n), Schwarz::<S>::new(n));
+        let x = shadow.coords(&shadow.generator(self));
+        let generators: Vec<Vec<usize>> = if self.is_snub() {
+            vec![vec![0, 1], vec![1, 2], vec![2, 0]]
+        } else {
+            vec![vec![0], vec![1], vec![2]]
+        };
+
+        let identity = [0, 1, 2].map(|i| [0, 1, 2].map(|j| ((i == j) as i64, 0)));
+        let mut maps = vec![identity];
+        let mut points = vec![shadow.position(&identity, &x)];
+        let mut i = 0;
+        while i < maps.len() {
+            for g in &generators {
+                let map = g.iter().fold(maps[i], |map, &k| reflect(&map, k, n));
+                let p = shadow.position(&map, &x);
+                if points.iter().all(|q| q.sub(&p).norm() > 1e-9) {
+                    maps.push(map);
+                    points.push(p);
+                }
+            }
+            i += 1;
+        }
+
+        let x = schwarz.coords(&schwarz.generator(self));
+        let positions = maps.iter().map(|map| schwarz.position(map, &x)).collect();
+        Mesh::from_faces(positions, &faces(&points)).expect("convex polyhedron")
+    }
+
+    /// Index of each number's corner in the triangle: 0, 1 and 2 for the 2-, 3- and n-fold ones.
+    fn corners(&self) -> [usize; 3] {
+        let mut used = [false; 3];
+        self.numbers.map(|k| {
+            let c = (0..3)
+                .find(|&c| !used[c] && [2, 3, self.n()][c] == k)
+                .expect("numbers of the triangle");
+            used[c] = true;
+            c
+        })
+    }
+}
+
+/// Number a + bτ, with τ = 2cos(π/n).
+type Coeff = (i64, i64);
+
+/// Linear map on coordinates in the basis of mirror normals.
+/// Reflections have entries 0, ±1 and ±τ there, so compositions stay in Z[τ].
+type Map = [[Coeff; 3]; 3];
+
+fn mul((a, b): Coeff, (c, d): Coeff, n: i64) -> Coeff {
+    // τ² = 1 + τ, 2 and 1 for n = 5, 4 and 3.
+    let (c0, c1) = match n {
+        5 => (1, 1),
+        4 => (2, 0),
+        _ => (1, 0),
+    };
+    (a * c + b * d * c0, a * d + b * c + b * d * c1)
+}
+
+/// Applies reflection `k` after `map`.
+///
+/// With normals at angle π - π/m between the mirrors, reflecting only changes the k-th coordinate,
+/// to minus itself plus 2cos(π/m) times each of the others.
+fn reflect(map: &Map, k: usize, n: i64) -> Map {
+    let mut out = *map;
+    for col in 0..3 {
+        let mut c = (-map[k][col].0, -map[k][col].1);
+        for j in (0..3).filter(|&j| j != k) {
+            // Mirrors j and k meet at the remaining corner, of angle π/2, π/3 or π/n.
+            let cos = [(0, 0), (1, 0), (0, 1)][3 - j - k];
+            let t = mul(cos, map[j][col], n);
+            c = (c.0 + t.0, c.1 + t.1);
+        }
+        out[k][col] = c;
+    }
+    out
+}
+
+/// The Schwarz triangle (2 3 n), with the n-fold corner on the z axis and the 2-fold one on the xz plane.
+struct Schwarz<S: Scalar> {
+    n: i64,
+    /// Corners, in the order 2, 3, n.
+    corners: [Vec3<S>; 3],
+    /// Unit normals of the sides, each opposite the corner at the same index.
+    mirrors: [Vec3<S>; 3],
+}
+
+impl<S: Scalar> Schwarz<S> {
+    fn new(n: i64) -> Self {
+        let angle = S::Angle::from_angle(&Angle::part(2 * n));
+        let (sin, cos) = (angle.sin(), angle.cos());
+        // Distances from the n-fold corner; both sides of a right triangle, from its angles.
+        let cos2 = S::int(1).div(&S::int(2).mul(&sin));
+        let cos3 = cos.div(&sin).div(&S::int(3).sqrt());
+        let sin2 = S::int(1).sub(&cos2.mul(&cos2)).sqrt();
+        let sin3 = S::int(1).sub(&cos3.mul(&cos3)).sqrt();
+        let corners = [
+            Vec3::new(sin2, S::int(0), cos2),
+            Vec3::new(sin3.mul(&cos), sin3.mul(&sin), cos3),
+            Vec3::new(S::int(0), S::int(0), S::int(1)),
+        ];
+        let mirrors = [0, 1, 2].map(|k| {
+            corners[(k + 1) % 3]
+                .cross(&corners[(k + 2) % 3])
+                .normalise()
+        });
+        Self {
+            n,
+            corners,
+            mirrors,
+        }
+    }
+
+    /// Coordinates of `v` in the basis of mirror normals.
+    fn coords(&self, v: &Vec3<S>) -> [S; 3] {
+        let m = &self.mirrors;
+        let det = m[0].dot(&m[1].cross(&m[2]));
+        [0, 1, 2].map(|k| v.dot(&m[(k + 1) % 3].cross(&m[(k + 2) % 3])).div(&det))
+    }
+
+    /// The point with coordinates `x`, moved by `map`.
+    fn position(&self, map: &Map, x: &[S; 3]) -> Vec3<S> {
+        let tau = S::int(2).mul(&S::Angle::from_angle(&Angle::part(2 * self.n)).cos());
+        (0..3).fold(Vec3::zero(), |sum, j| {
+            let c = (0..3)
+                .filter(|&k| map[j][k] != (0, 0))
+                .map(|k| {
+                    let (a, b) = map[j][k];
+                    S::int(a).add(&S::int(b).mul(&tau)).mul(&x[k])
+                })
+                .reduce(|a, b| a.add(&b));
+            match c {
+                Some(c) => sum.add(&self.mirrors[j].scale(&c)),
+                None => sum,
+            }
+        })
+    }
+
+    /// The generating point of the symbol.
+    fn generator(&self, w: &Wythoff) -> Vec3<S> {
+        let [a, b, c] = w.corners().map(|k| &self.corners[k]);
+        let sin = |p: &Vec3<S>, q: &Vec3<S>| p.cross(q).norm();
+        match w.bar {
+            0 => self.snub(),
+            1 => a.clone(),
+            2 => a.scale(&sin(c, b)).add(&b.scale(&sin(c, a))).normalise(),
+            _ => a
+                .scale(&sin(b, c))
+                .add(&b.scale(&sin(c, a)))
+                .add(&c.scale(&sin(a, b)))
+                .normalise(),
+        }
+    }
+
+    /// The snub's generating point.
+    ///
+    /// Rotating it by 2π/k around the k-fold corner, at distance ρ, moves it by 2 sin ρ sin(π/k); these are the edges,
+    /// so they are all equal, to 2 sin(e/2) say.
+    /// With w = 1 / cos²(e/2), this comes down to w³ - 8w² + 20w - 16 + 4cos²(π/n) = 0.
+    fn snub(&self) -> Vec3<S> {
+        let angle = S::Angle::from_angle(&Angle::part(2 * self.n));
+        let (sin, cos) = (angle.sin(), angle.cos());
+        let third = |a: &S| a.div(&S::int(3));
+
+        // With w = y + 8/3, y³ + py + q = 0.
+        let p = third(&S::int(-4));
+        let q = S::int(4)
+            .mul(&cos.mul(&cos))
+            .sub(&S::int(16).div(&S::int(27)));
+        let w = if self.n == 3 {
+            // Three real roots, in trigonometric form: 3.62, 3 and 1.38 for k = 0, 1, 2.
+            // The last one gives the icosahedron.
+            let r = S::int(2).mul(&third(&p.neg()).sqrt());
+            let theta = S::int(3)
+                .mul(&q)
+                .div(&S::int(2).mul(&p))
+                .mul(&S::int(-3).div(&p).sqrt())
+                .acos()
+                .idiv(3);
+            let turn = <S::Angle as ScalarAngle<S>>::turn();
+            r.mul(&theta.sub(&turn.imul(2).idiv(3)).cos())
+                .add(&third(&S::int(8)))
+        } else {
+            // One real root, by Cardano's formula.
+            let d = q.mul(&q).div(&S::int(4)).add(&p.ipow(3).div(&S::int(27)));
+            let t = q.div(&S::int(2)).add(&d.sqrt()).cbrt();
+            p.div(&S::int(3).mul(&t)).sub(&t).add(&third(&S::int(8)))
+        };
+
+        // Distances to the 3- and n-fold corners.
+        let half = S::int(1).sub(&S::int(1).div(&w));
+        let cos3 = S::int(1).sub(&S::int(4).mul(&half).div(&S::int(3))).sqrt();
+        let cosn = S::int(1).sub(&half.div(&sin.mul(&sin))).sqrt();
+
+        // The point at those distances, on the same side of their arc as the 2-fold corner.
+        let [_, c3, cn] = &self.corners;
+        let g = cn.dot(c3);
+        let det = S::int(1).sub(&g.mul(&g));
+        let alpha = cosn.sub(&g.mul(&cos3)).div(&det);
+        let beta = cos3.sub(&g.mul(&cosn)).div(&det);
+        let along = alpha
+            .mul(&alpha)
+            .add(&beta.mul(&beta))
+            .add(&S::int(2).mul(&alpha).mul(&beta).mul(&g));
+        let gamma = S::int(1).sub(&along).div(&det).sqrt();
+        cn.scale(&alpha)
+            .add(&c3.scale(&beta))
+            .add(&c3.cross(cn).scale(&gamma))
+    }
+}
+
+/// Faces of the convex hull of points on the unit sphere, wound outwards, assuming that edges are the shortest chords.
+fn faces(points: &[Vec3<f64>]) -> Vec<Vec<usize>> {
+    let dist = |i: usize, j: usize| points[i].sub(&points[j]).norm();
+    let n = points.len();
+    let edge = (0..n)
+        .flat_map(|i| (0..i).map(move |j| (i, j)))
+        .map(|(i, j)| dist(i, j))
+        .fold(f64::INFINITY, f64::min);
+
+    // Neighbours of each vertex, counterclockwise as seen from the outside, by angle in the tangent plane.
+    let neighbours: Vec<Vec<usize>> = (0..n)
+        .map(|v| {
+            let p = &points[v];
+            let mut around: Vec<usize> = (0..n)
+                .filter(|&u| u != v && dist(u, v) < edge * (1.0 + 1e-6))
+                .collect();
+            let x = points[around[0]].sub(&p.scale(&p.dot(&points[around[0]])));
+            let y = p.cross(&x);
+            let angle = |u: usize| {
+                let d = points[u].sub(p);
+                d.dot(&y).atan2(d.dot(&x))
+            };
+            around.sort_by(|&a, &b| angle(a).total_cmp(&angle(b)));
+            around
+        })
+        .collect();
+
+    trace_faces(&neighbours)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::platonic::Platonic;
+    use crate::testing::{assert_agree, counts, outward};
+    use crate::val::Val;
+    use approx::assert_relative_eq;
+
+    fn length(m: &Mesh<Vec3<f64>>, h: usize) -> f64 {
+        m.position(m.half_edge(h).origin)
+            .sub(m.position(m.dest(h)))
+            .norm()
+    }
+
+    #[test]
+    fn test_uniform() {
+        for (symbol, expected) in [
+            ("5 | 2 3", (12, 30, 20)),
+            ("3 | 2 5", (20, 30, 12)),
+            ("2 | 3 5", (30, 60, 32)),
+            ("2 5 | 3", (60, 90, 32)),
+            ("2 3 | 5", (60, 90, 32)),
+            ("3 5 | 2", (60, 120, 62)),
+            ("2 3 5 |", (120, 180, 62)),
+            ("| 2 3 5", (60, 150, 92)),
+            ("4 | 2 3", (6, 12, 8)),
+            ("3 | 2 4", (8, 12, 6)),
+            ("2 | 3 4", (12, 24, 14)),
+            ("3 4 | 2", (24, 48, 26)),
+            ("2 3 4 |", (48, 72, 26)),
+            ("| 2 3 4", (24, 60, 38)),
+            ("3 | 2 3", (4, 6, 4)),
+            ("2 | 3 3", (6, 12, 8)),
+            ("2 3 3 |", (24, 36, 14)),
+            ("| 2 3 3", (12, 30, 20)),
+        ] {
+            let w = Wythoff::parse(symbol).unwrap();
+            let m = w.polyhedron::<f64>();
+            assert_eq!(counts(&m), expected, "{symbol}");
+            assert!(m.is_closed());
+
+            // Vertices on the sphere, equal edges, faces wound outwards.
+            let edge = length(&m, m.edges().next().unwrap());
+            for v in m.vertices() {
+                assert_relative_eq!(m.position(v).norm(), 1.0, epsilon = 1e-12);
+            }
+            for h in m.edges() {
+                assert_relative_eq!(length(&m, h), edge, epsilon = 1e-12);
+            }
+            assert!(outward(&m), "{symbol}");
+        }
+
+        // Circumradius in units of the edge, as published.
+        for (symbol, r) in [
+            ("2 5 | 3", 2.4780186590),
+            ("| 2 3 4", 1.3437133737),
+            ("| 2 3 5", 2.1558373751),
+        ] {
+            let m = Wythoff::parse(symbol).unwrap().polyhedron::<f64>();
+            assert_relative_eq!(
+                1.0 / length(&m, m.edges().next().unwrap()),
+                r,
+                epsilon = 1e-9
+            );
+        }
+
+        assert_eq!(Wythoff::parse("25|3"), Wythoff::parse("2 5 | 3"));
+        for symbol in ["2 5 3", "2 | 5 | 3", "2 4 | 5", "| 2 2 5", "2 3 | 6"] {
+            assert!(Wythoff::parse(symbol).is_none(), "{symbol}");
+        }
+    }
+
+    #[test]
+    fn test_exact() {
+        // The icosahedron, from both its own triangle and the tetrahedron's snub;
+        // the snub cube and dodecahedron, by Cardano's formula.
+        let solid = Platonic::Icosahedron.mesh::<f64>();
+        for symbol in ["5 | 2 3", "| 2 3 3", "| 2 3 4", "| 2 3 5"] {
+            let m = Wythoff::parse(symbol).unwrap().polyhedron::<Val>();
+            let numeric = Wythoff::parse(symbol).unwrap().polyhedron::<f64>();
+            assert_agree(m.positions(), numeric.positions());
+            if symbol == "5 | 2 3" {
+                for q in numeric.positions() {
+                    assert!(solid.positions().iter().any(|p| p.sub(q).norm() < 1e-9));
+                }
+            }
+        }
+    }
+}
@@ -1,5 +1,5 @@
 const { PI } = Math;
-const { ArcRotateCamera, Color4, Engine, HemisphericLight, Mesh, MeshBuilder, Quaternion, Scene, Space, Vector3, VertexData } = BABYLON;
+const { ArcRotateCamera, Color4, Engine, HemisphericLight, Mesh, Quaternion, Scene, Space, Vector3, VertexData } = BABYLON;
 const { WORLD } = Space;
 const { X, Y, R, O } = await consts();
 
@@ -71,7 +71,7 @@ async function draw(shape) {
 
   const geometry = await (await req).json();
   const updates = geometry.meshes.map(data => {
-    let params = geometry.params.map(pval);
+    let params = geometry.params.map(p => pval(p.default));
     data.positions = data.positions.map(pos => pfun(pos, params.length));
     const meshes = symmetry(data);
 
@@ -93,44 +93,42 @@ async function draw(shape) {
       update = false;
 
       vd.positions = data.positions.map(fn => fn.apply(null, params));
-      meshes.forEach(m => {
-        if (m.name.startsWith("dbg.")) {
-          m.position = pvec(vd.positions);
-        } else {
-          vd.applyToMesh(m);
-        }
-      });
+      meshes.forEach(m => vd.applyToMesh(m));
     };
   });
 
   const params = document.body.querySelector(".params");
   Array.from(params.children).forEach(elem => elem.remove());
-  params.append(...geometry.params.map((t, i) => {
-    const sub = document.createElement("sub");
-    sub.innerText = i + 1;
-
+  params.append(...geometry.params.map((p, i) => {
+    const [sym, idx] = p.symbol.split("_");
     const em = document.createElement("em");
-    em.append("t", sub);
+    em.append(sym);
+    if (idx !== undefined) {
+      const sub = document.createElement("sub");
+      sub.innerText = idx;
+      em.append(sub);
+    }
 
     const name = `t${i}`;
     const output = document.createElement("output");
     output.setAttribute("for", name);
-    output.value = pval(t).toFixed(2);
+    output.value = pval(p.default).toFixed(2);
 
     const input = document.createElement("input");
     Object.entries({
       name,
       id: name,
       type: "range",
-      step: "0.01",
-      min: "0",
-      max: "1",
-      value: t,
+      step: p.step,
+      min: p.min,
+      max: p.max,
+      value: pval(p.default),
     }).forEach(([key, val]) => input.setAttribute(key, val));
 
 
     const label = document.createElement("label");
-    label.append(em, " = ", output, input);
+    label.title = [p.name, p.description].filter(s => s).join(": ");
+    label.append(em, " = ", output, ...(p.unit ? [" ", p.unit] : []), input);
 
     return label;
   }));
@@ -155,7 +153,7 @@ function symmetry(mesh) {
     case "icos.f.3": return symIcosF3();
     case "icos.f.c": return symIcosFC(mesh);
     case "icos.v.1": return symIcosV1();
-    case "dbg": return symDbg();
+    case "none": return [new Mesh("none")];
     default:
       throw new Error(`symmetry not supported: ${mesh.symmetry}`);
   }
@@ -252,12 +250,6 @@ function symIcosV1() {
   return meshes;
 }
 
-function symDbg() {
-  return [
-    MeshBuilder.CreateSphere(`dbg.${Math.random()}`, { diameter: 0.05 }),
-  ];
-}
-
 
 function meshList(prefix, count) {
   return Array(count)